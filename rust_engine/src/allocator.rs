@@ -0,0 +1,227 @@
+use crate::config::Config;
+use crate::engine::Opportunity;
+use std::collections::HashMap;
+
+/// Result of one allocation pass
+pub struct AllocationSummary {
+    pub funded: usize,
+    pub capital: f64,
+}
+
+/// Sizes a cycle's ranked opportunities against real venue cash.
+///
+/// Each opportunity is scaled down from its Kelly target until every venue it
+/// touches has the cash for its legs (fees included) and no event goes over the
+/// exposure cap. Earlier (better ranked) opportunities are funded first, and
+/// committed positions count against both limits.
+pub struct PortfolioAllocator {
+    /// Remaining cash per venue; `None` when no balances are configured
+    venue_cash: Option<HashMap<String, f64>>,
+    /// Single pool used when balances are not split per venue
+    shared_cash: f64,
+    event_cap: f64,
+    event_exposure: HashMap<String, f64>,
+}
+
+impl PortfolioAllocator {
+    pub fn new(config: &Config, total_capital: f64) -> Self {
+        let mut event_exposure: HashMap<String, f64> = HashMap::new();
+        let mut venue_cash = if config.balances.is_empty() {
+            None
+        } else {
+            Some(config.balances.clone())
+        };
+        let mut shared_cash = total_capital;
+
+        let capital = if config.balances.is_empty() {
+            total_capital
+        } else {
+            config.balances.values().sum()
+        };
+
+        for pos in &config.committed_positions {
+            match venue_cash.as_mut() {
+                Some(cash) => *cash.entry(pos.platform.clone()).or_insert(0.0) -= pos.amount,
+                None => shared_cash -= pos.amount,
+            }
+            if let Some(event) = &pos.event {
+                *event_exposure.entry(format!("{}:{}", pos.platform, event)).or_insert(0.0) += pos.amount;
+            }
+        }
+
+        Self {
+            venue_cash,
+            shared_cash,
+            event_cap: capital * config.max_event_exposure_percent / 100.0,
+            event_exposure,
+        }
+    }
+
+    /// Overwrite `suggested_position` with a feasible size, in list order
+    pub fn allocate(&mut self, opportunities: &mut [Opportunity]) -> AllocationSummary {
+        let mut summary = AllocationSummary { funded: 0, capital: 0.0 };
+
+        for opp in opportunities.iter_mut() {
+            // Cash needed per basket unit, by venue and by event
+            let mut per_venue: HashMap<&str, f64> = HashMap::new();
+            let mut per_event: HashMap<&str, f64> = HashMap::new();
+            for leg in &opp.legs {
+                *per_venue.entry(&leg.platform).or_insert(0.0) += leg.cost() + leg.fee;
+                *per_event.entry(&leg.event_key).or_insert(0.0) += leg.cost() + leg.fee;
+            }
+            let unit_cost: f64 = per_venue.values().sum();
+            if unit_cost <= 0.0 {
+                continue;
+            }
+
+            let mut units = opp.suggested_position / unit_cost;
+            let mut limit = None;
+
+            let mut cap = |available: f64, need: f64, label: String| {
+                let fits = available.max(0.0) / need;
+                if fits < units {
+                    units = fits;
+                    limit = Some(label);
+                }
+            };
+
+            match &self.venue_cash {
+                Some(cash) => {
+                    for (venue, need) in &per_venue {
+                        cap(cash.get(*venue).copied().unwrap_or(0.0), *need, format!("{} cash", venue));
+                    }
+                }
+                None => cap(self.shared_cash, unit_cost, "capital".to_string()),
+            }
+            for (event, need) in &per_event {
+                let used = self.event_exposure.get(*event).copied().unwrap_or(0.0);
+                cap(self.event_cap - used, *need, format!("{} exposure cap", event));
+            }
+
+            let allocated = units * unit_cost;
+            opp.suggested_position = allocated;
+            opp.sizing_limit = limit;
+            if allocated <= 0.0 {
+                continue;
+            }
+
+            match self.venue_cash.as_mut() {
+                Some(cash) => {
+                    for (venue, need) in &per_venue {
+                        *cash.entry(venue.to_string()).or_insert(0.0) -= need * units;
+                    }
+                }
+                None => self.shared_cash -= allocated,
+            }
+            for (event, need) in &per_event {
+                *self.event_exposure.entry(event.to_string()).or_insert(0.0) += need * units;
+            }
+
+            summary.funded += 1;
+            summary.capital += allocated;
+        }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Leg, StrategyKind, Timing};
+    use crate::lp_solver::Side;
+
+    /// Opportunity with fee-free legs of (venue, event, price) and a Kelly target of `position`
+    fn opp(legs: &[(&str, &str, f64)], position: f64) -> Opportunity {
+        let legs: Vec<Leg> = legs.iter()
+            .map(|&(platform, event, price)| Leg {
+                market_key: format!("{}:{}-m", platform, event),
+                event_key: format!("{}:{}", platform, event),
+                platform: platform.to_string(),
+                question: String::new(),
+                url: String::new(),
+                side: Side::Yes,
+                outcome: "Yes".to_string(),
+                outcome_index: 0,
+                limit_price: price,
+                size: 1.0,
+                fee: 0.0,
+                close_time: None,
+                fetched_at: None,
+                venue_time: None,
+            })
+            .collect();
+        let total_cost = legs.iter().map(|l| l.cost()).sum();
+        Opportunity {
+            id: String::new(),
+            kind: StrategyKind::Combinatorial,
+            description: String::new(),
+            legs,
+            total_cost,
+            gross_profit: 0.0,
+            net_profit_after_fees: 0.0,
+            roi_percent: 0.0,
+            suggested_position: position,
+            days_to_resolution: None,
+            annualized_roi_percent: None,
+            confidence: 1.0,
+            resolution: None,
+            sizing_limit: None,
+            confirmation: None,
+            timing: Timing::default(),
+        }
+    }
+
+    fn allocator(config: &str, total_capital: f64) -> PortfolioAllocator {
+        PortfolioAllocator::new(&serde_json::from_str(config).unwrap(), total_capital)
+    }
+
+    #[test]
+    fn venue_cash_caps_each_leg_and_is_used_up_in_rank_order() {
+        let mut allocator = allocator(r#"{"balances": {"Kalshi": 100, "Polymarket": 1000}, "max_event_exposure_percent": 100}"#, 0.0);
+        let mut opps = [
+            // $0.50 of every $0.90 unit is on Kalshi: $100 there buys 200 units
+            opp(&[("Kalshi", "E1", 0.5), ("Polymarket", "E2", 0.4)], 450.0),
+            opp(&[("Kalshi", "E3", 0.5)], 50.0),
+            opp(&[("Polymarket", "E4", 0.5)], 50.0),
+        ];
+        let summary = allocator.allocate(&mut opps);
+
+        assert!((opps[0].suggested_position - 180.0).abs() < 1e-9);
+        assert_eq!(opps[0].sizing_limit.as_deref(), Some("Kalshi cash"));
+        assert_eq!(opps[1].suggested_position, 0.0);
+        assert!((opps[2].suggested_position - 50.0).abs() < 1e-9);
+        assert_eq!(opps[2].sizing_limit, None);
+        assert_eq!(summary.funded, 2);
+        assert!((summary.capital - 230.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn event_cap_counts_committed_positions() {
+        // 10% of $1,000 per event, $60 of it already held
+        let mut allocator = allocator(r#"{
+            "max_event_exposure_percent": 10,
+            "committed_positions": [{"platform": "Kalshi", "event": "E", "amount": 60}]
+        }"#, 1000.0);
+        let mut opps = [
+            opp(&[("Kalshi", "E", 1.0)], 200.0),
+            opp(&[("Kalshi", "F", 1.0)], 50.0),
+        ];
+        allocator.allocate(&mut opps);
+
+        assert!((opps[0].suggested_position - 40.0).abs() < 1e-9);
+        assert_eq!(opps[0].sizing_limit.as_deref(), Some("Kalshi:E exposure cap"));
+        assert!((opps[1].suggested_position - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn shared_capital_caps_without_venue_balances() {
+        let mut allocator = allocator(r#"{"max_event_exposure_percent": 100}"#, 100.0);
+        let mut opps = [opp(&[("Kalshi", "E", 0.5), ("Polymarket", "F", 0.4)], 150.0)];
+        let summary = allocator.allocate(&mut opps);
+
+        assert!((opps[0].suggested_position - 100.0).abs() < 1e-9);
+        assert_eq!(opps[0].sizing_limit.as_deref(), Some("capital"));
+        assert_eq!(summary.funded, 1);
+    }
+}
@@ -0,0 +1,310 @@
+use crate::allocator::PortfolioAllocator;
+use crate::config::Config;
+use crate::cross_matcher::CrossMatcher;
+use crate::engine::{ArbitrageEngine, Market, Opportunity, StrategyKind};
+use crate::history::PriceHistory;
+use crate::lifecycle::{EventKind, OpportunityTracker};
+use crate::lp_solver::Side;
+use crate::market_filter::{FilterStats, MarketFilter};
+use crate::revalidate::reprice;
+use crate::rules::RuleSet;
+use crate::strategy::StrategySettings;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+
+/// One recorded scan, rebuilt from price history
+pub struct Snapshot {
+    pub at: DateTime<Utc>,
+    pub markets: Vec<Market>,
+}
+
+/// Winning outcome label per market key, e.g. {"Kalshi:KXFED-25DEC": "Yes"}
+pub type Resolutions = HashMap<String, String>;
+
+/// One opportunity from the scan it opened in until it closed (or the replay ended)
+#[derive(Debug, Clone, Serialize)]
+pub struct OpportunityResult {
+    pub id: String,
+    pub kind: StrategyKind,
+    pub description: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub duration_secs: i64,
+    pub scans_seen: usize,
+    /// Still open at the last replayed scan
+    pub open_at_end: bool,
+    pub open_roi_percent: f64,
+    pub peak_roi_percent: f64,
+    pub suggested_position: f64,
+    /// ROI at open times the suggested position
+    pub theoretical_pnl: f64,
+    /// Set when every leg's market has a resolution
+    pub realized_pnl: Option<f64>,
+    /// Whether it still cleared its profit floor repriced at the next scan,
+    /// the replay's stand-in for revalidation (None on the last scan)
+    pub confirmed_next_scan: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyResult {
+    pub kind: StrategyKind,
+    pub opportunities: usize,
+    pub median_duration_secs: i64,
+    pub theoretical_pnl: f64,
+    pub realized_pnl: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub scans: usize,
+    pub min_roi_percent: f64,
+    pub min_profit_threshold: f64,
+    /// Distinct market pairs the CrossMatcher matched at least once
+    pub cross_matches: usize,
+    pub theoretical_pnl: f64,
+    /// Over the resolved opportunities only
+    pub realized_pnl: Option<f64>,
+    pub resolved: usize,
+    /// Share of opportunities still valid at the next scan, over those with a next scan
+    pub precision: Option<f64>,
+    pub strategies: Vec<StrategyResult>,
+    pub opportunities: Vec<OpportunityResult>,
+}
+
+/// Rebuild every `every`th recorded scan between `from` and `to`
+pub fn load_snapshots(history: &PriceHistory, from: DateTime<Utc>, to: DateTime<Utc>, every: usize) -> Result<Vec<Snapshot>, Box<dyn Error>> {
+    history.scans(from, to)?
+        .into_iter()
+        .step_by(every.max(1))
+        .map(|at| Ok(Snapshot { at, markets: history.snapshot(at)? }))
+        .collect()
+}
+
+/// Profit of the opportunity at its suggested size once all legs resolve
+fn realized_pnl(opp: &Opportunity, outcome_counts: &HashMap<String, usize>, resolutions: &Resolutions) -> Option<f64> {
+    if opp.total_cost <= 0.0 {
+        return None;
+    }
+    let mut payout = 0.0;
+    for leg in &opp.legs {
+        let winner = resolutions.get(&leg.market_key)?;
+        let named = leg.outcome.eq_ignore_ascii_case(winner);
+        // Binary legs name the side they buy; a multi-outcome NO leg pays unless its outcome wins
+        let wins = match (outcome_counts.get(&leg.market_key).copied().unwrap_or(2), leg.side) {
+            (2, _) | (_, Side::Yes) => named,
+            (_, Side::No) => !named,
+        };
+        if wins {
+            payout += leg.size;
+        }
+    }
+    let scale = opp.suggested_position / opp.total_cost;
+    Some(scale * (payout - opp.total_cost - opp.total_fees()))
+}
+
+fn median(values: &mut [i64]) -> i64 {
+    if values.is_empty() {
+        return 0;
+    }
+    values.sort_unstable();
+    values[values.len() / 2]
+}
+
+/// Run the scans through the filters, CrossMatcher, engine and allocator as the live loop would
+pub fn replay(snapshots: &[Snapshot], config: &Config, total_capital: f64, env_min_liquidity: Option<f64>, resolutions: &Resolutions) -> BacktestReport {
+    let mut engine = ArbitrageEngine::new(config, total_capital, RuleSet::load());
+    let matcher = CrossMatcher::new(&config.matching).quiet();
+    let filter = MarketFilter::new(config, env_min_liquidity);
+    let mut tracker = OpportunityTracker::new(&config.lifecycle);
+
+    let mut results: Vec<OpportunityResult> = Vec::new();
+    let mut open: HashMap<String, usize> = HashMap::new();
+    let mut matched_pairs: HashSet<(String, String)> = HashSet::new();
+    // Opened last scan, waiting to be repriced against this one
+    let mut unconfirmed: Vec<(usize, Opportunity)> = Vec::new();
+
+    for snapshot in snapshots {
+        let quotes: HashMap<String, Market> = snapshot.markets.iter().map(|m| (m.key(), m.clone())).collect();
+        for (i, mut opp) in unconfirmed.drain(..) {
+            let min_profit = StrategySettings::from_config(config, opp.kind).min_profit;
+            results[i].confirmed_next_scan = Some(reprice(&mut opp, &quotes, min_profit).is_ok());
+        }
+
+        let mut markets = snapshot.markets.clone();
+        filter.apply(&mut markets, &mut FilterStats::default(), snapshot.at);
+        let outcome_counts: HashMap<String, usize> = markets.iter().map(|m| (m.key(), m.outcome_prices.len())).collect();
+
+        let mut platform_markets: HashMap<String, Vec<&Market>> = HashMap::new();
+        for m in &markets {
+            platform_markets.entry(m.platform.clone()).or_default().push(m);
+        }
+        let mut cross_matches = matcher.match_all(&platform_markets);
+        cross_matches.extend(matcher.match_duplicates(&platform_markets));
+        matched_pairs.extend(cross_matches.iter().map(|cm| cm.market_keys()));
+
+        engine.as_of = Some(snapshot.at);
+        let mut report = engine.analyze_markets(&markets, &mut cross_matches);
+        PortfolioAllocator::new(config, total_capital).allocate(&mut report.opportunities);
+
+        for event in tracker.observe(&report.opportunities, snapshot.at) {
+            let record = &event.record;
+            let opp = &record.opportunity;
+            match event.kind {
+                EventKind::Opened => {
+                    tracker.mark_alerted(&opp.id, opp.roi_percent);
+                    open.insert(opp.id.clone(), results.len());
+                    results.push(OpportunityResult {
+                        id: opp.id.clone(),
+                        kind: opp.kind,
+                        description: opp.description.clone(),
+                        first_seen: record.first_seen,
+                        last_seen: record.last_seen,
+                        duration_secs: 0,
+                        scans_seen: 0,
+                        open_at_end: true,
+                        open_roi_percent: opp.roi_percent,
+                        peak_roi_percent: record.peak_roi,
+                        suggested_position: opp.suggested_position,
+                        theoretical_pnl: opp.roi_percent / 100.0 * opp.suggested_position,
+                        realized_pnl: realized_pnl(opp, &outcome_counts, resolutions),
+                        confirmed_next_scan: None,
+                    });
+                    unconfirmed.push((results.len() - 1, opp.clone()));
+                }
+                EventKind::Changed => tracker.mark_alerted(&opp.id, opp.roi_percent),
+                EventKind::Closed => {
+                    if let Some(i) = open.remove(&opp.id) {
+                        results[i].open_at_end = false;
+                    }
+                }
+            }
+        }
+
+        for opp in &report.opportunities {
+            if let Some(&i) = open.get(&opp.id) {
+                let result = &mut results[i];
+                result.last_seen = snapshot.at;
+                result.scans_seen += 1;
+                result.peak_roi_percent = result.peak_roi_percent.max(opp.roi_percent);
+                result.duration_secs = (result.last_seen - result.first_seen).num_seconds();
+            }
+        }
+    }
+
+    let mut by_kind: BTreeMap<&'static str, Vec<&OpportunityResult>> = BTreeMap::new();
+    for result in &results {
+        by_kind.entry(result.kind.config_key()).or_default().push(result);
+    }
+    let sum_realized = |rs: &[&OpportunityResult]| {
+        let realized: Vec<f64> = rs.iter().filter_map(|r| r.realized_pnl).collect();
+        (!realized.is_empty()).then(|| realized.iter().sum())
+    };
+    let strategies = by_kind.values()
+        .map(|rs| StrategyResult {
+            kind: rs[0].kind,
+            opportunities: rs.len(),
+            median_duration_secs: median(&mut rs.iter().map(|r| r.duration_secs).collect::<Vec<_>>()),
+            theoretical_pnl: rs.iter().map(|r| r.theoretical_pnl).sum(),
+            realized_pnl: sum_realized(rs),
+        })
+        .collect();
+
+    let all: Vec<&OpportunityResult> = results.iter().collect();
+    let checked: Vec<bool> = results.iter().filter_map(|r| r.confirmed_next_scan).collect();
+    BacktestReport {
+        from: snapshots.first().map(|s| s.at),
+        to: snapshots.last().map(|s| s.at),
+        scans: snapshots.len(),
+        min_roi_percent: config.min_roi_percent,
+        min_profit_threshold: config.min_profit_threshold,
+        cross_matches: matched_pairs.len(),
+        theoretical_pnl: results.iter().map(|r| r.theoretical_pnl).sum(),
+        realized_pnl: sum_realized(&all),
+        resolved: results.iter().filter(|r| r.realized_pnl.is_some()).count(),
+        precision: (!checked.is_empty()).then(|| checked.iter().filter(|ok| **ok).count() as f64 / checked.len() as f64),
+        strategies,
+        opportunities: results,
+    }
+}
+
+pub fn print_summary(report: &BacktestReport) {
+    let when = |t: Option<DateTime<Utc>>| t.map(|t| t.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_else(|| "-".to_string());
+    let money = |v: Option<f64>| v.map(|v| format!("${:.2}", v)).unwrap_or_else(|| "-".to_string());
+
+    println!("📼 Backtest {} → {} UTC: {} scans, {} cross-matched pairs",
+        when(report.from), when(report.to), report.scans, report.cross_matches);
+    println!("{:<18} {:>6} {:>12} {:>12} {:>12}", "strategy", "opps", "median life", "theoretical", "realized");
+    for s in &report.strategies {
+        println!("{:<18} {:>6} {:>11}s {:>12} {:>12}",
+            s.kind.label(), s.opportunities, s.median_duration_secs, money(Some(s.theoretical_pnl)), money(s.realized_pnl));
+    }
+    println!("{:<18} {:>6} {:>12} {:>12} {:>12}",
+        "total", report.opportunities.len(), "", money(Some(report.theoretical_pnl)), money(report.realized_pnl));
+    if let Some(precision) = report.precision {
+        println!("🎯 Precision: {:.0}% still valid at the next scan", precision * 100.0);
+    }
+    if report.resolved > 0 {
+        println!("✅ {} of {} opportunities resolved", report.resolved, report.opportunities.len());
+    }
+}
+
+/// `--name value` pairs, rejecting names not in `allowed`
+pub fn parse_flags(args: &[String], allowed: &[&str]) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    let mut flags = HashMap::new();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let name = flag.strip_prefix("--").filter(|name| allowed.contains(name))
+            .ok_or_else(|| format!("unknown option {}", flag))?;
+        let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        flags.insert(name.to_string(), value.clone());
+    }
+    Ok(flags)
+}
+
+/// `TOTAL_CAPITAL` and `MIN_LIQUIDITY` as the live loop reads them
+pub fn env_capital() -> (f64, Option<f64>) {
+    let total_capital = std::env::var("TOTAL_CAPITAL").ok().and_then(|v| v.parse().ok()).unwrap_or(1000.0);
+    let env_min_liquidity = std::env::var("MIN_LIQUIDITY").ok().and_then(|v| v.parse().ok());
+    (total_capital, env_min_liquidity)
+}
+
+/// Scans named by `--hours` (default 24) and `--every` (default 1), and the `--resolutions` file if given
+pub fn load_inputs(config: &Config, flags: &HashMap<String, String>) -> Result<(Vec<Snapshot>, Resolutions), Box<dyn Error>> {
+    let hours: f64 = flags.get("hours").map(|h| h.parse()).transpose()?.unwrap_or(24.0);
+    let every: usize = flags.get("every").map(|e| e.parse()).transpose()?.unwrap_or(1);
+    let resolutions: Resolutions = match flags.get("resolutions") {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+        None => Resolutions::new(),
+    };
+
+    let history = PriceHistory::open(&config.history.path)?;
+    let to = Utc::now();
+    let from = to - Duration::minutes((hours * 60.0) as i64);
+    let snapshots = load_snapshots(&history, from, to, every)?;
+    if snapshots.is_empty() {
+        return Err(format!("no recorded scans in the last {}h of {}", hours, config.history.path).into());
+    }
+    Ok((snapshots, resolutions))
+}
+
+/// `backtest [--hours N] [--every N] [--resolutions FILE] [--out FILE]`
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let flags = parse_flags(args, &["hours", "every", "resolutions", "out"])?;
+    let out = flags.get("out").map(String::as_str).unwrap_or("backtest_report.json");
+
+    let config = Config::load();
+    let (total_capital, env_min_liquidity) = env_capital();
+    let (snapshots, resolutions) = load_inputs(&config, &flags)?;
+    println!("📼 Replaying {} scans...", snapshots.len());
+
+    let report = replay(&snapshots, &config, total_capital, env_min_liquidity, &resolutions);
+    fs::write(out, serde_json::to_string_pretty(&report)?)?;
+    print_summary(&report);
+    println!("📝 Report written to {}", out);
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use crate::engine::Market;
+use crate::proposition::{DeadlineKind, PropositionParser, ThresholdUnit};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+// Worlds are bitmasks over the family's markets
+pub const MAX_TERM_POINTS: usize = 32;
+
+/// One market in a term structure
+#[derive(Debug, Clone, Copy)]
+pub struct TermPoint {
+    pub market: usize,
+    pub deadline: NaiveDate,
+}
+
+/// Annualized rate at which the event is priced to happen between two deadlines,
+/// given it hasn't by the first: ln(S1 / S2) / years with S = 1 - P(by deadline).
+/// Negative means the later deadline is priced below the earlier one.
+#[derive(Debug, Clone, Copy)]
+pub struct HazardRate {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub annual_rate: f64,
+}
+
+/// Markets that differ only in deadline: "X by March 31", "X by June 30", "X in 2025"
+#[derive(Debug, Clone)]
+pub struct TermStructure {
+    pub subject: String,
+    /// Sorted by deadline
+    pub points: Vec<TermPoint>,
+}
+
+impl TermStructure {
+    fn deadlines(&self) -> Vec<NaiveDate> {
+        let mut deadlines: Vec<NaiveDate> = self.points.iter().map(|p| p.deadline).collect();
+        deadlines.dedup();
+        deadlines
+    }
+
+    /// One world per window the event can happen in, plus "never";
+    /// bit `i` is set when point `i` resolves YES
+    pub fn worlds(&self) -> Vec<u32> {
+        let mut worlds = vec![0u32];
+        for deadline in self.deadlines() {
+            worlds.push(self.points.iter().enumerate()
+                .filter(|(_, p)| p.deadline >= deadline)
+                .fold(0u32, |world, (i, _)| world | (1 << i)));
+        }
+        worlds
+    }
+
+    /// YES price per deadline, averaged across venues listing the same date
+    fn curve(&self, markets: &[Market]) -> Vec<(NaiveDate, f64)> {
+        self.deadlines().into_iter()
+            .map(|deadline| {
+                let prices: Vec<f64> = self.points.iter()
+                    .filter(|p| p.deadline == deadline)
+                    .map(|p| markets[p.market].outcome_prices[0])
+                    .collect();
+                (deadline, prices.iter().sum::<f64>() / prices.len() as f64)
+            })
+            .collect()
+    }
+
+    /// Hazard rate between each pair of consecutive deadlines
+    pub fn hazard_rates(&self, markets: &[Market]) -> Vec<HazardRate> {
+        self.curve(markets).windows(2)
+            .map(|pair| {
+                let ((from, p1), (to, p2)) = (pair[0], pair[1]);
+                let years = (to - from).num_days() as f64 / 365.0;
+                // Clamp so a 100% price doesn't divide by zero
+                let survival = |p: f64| (1.0 - p).clamp(0.005, 1.0);
+                HazardRate { from, to, annual_rate: (survival(p1) / survival(p2)).ln() / years }
+            })
+            .collect()
+    }
+
+    /// Human-readable term structure for the alert
+    pub fn describe(&self, markets: &[Market]) -> String {
+        let hazards = self.hazard_rates(markets);
+        let violations = hazards.iter().filter(|h| h.annual_rate < 0.0).count();
+        let chain: Vec<String> = hazards.iter()
+            .map(|h| format!("{}→{} {:+.0}%/yr", h.from.format("%b %d"), h.to.format("%b %d"), h.annual_rate * 100.0))
+            .collect();
+        format!("{} inverted window(s), hazard {}", violations, chain.join(", "))
+    }
+}
+
+/// Group "by"-deadline markets that share subject, direction and threshold.
+/// Markets without a parsed deadline fall back to their close date.
+pub fn assemble(parser: &PropositionParser, markets: &[Market], reference: impl Fn(&Market) -> Option<NaiveDate>) -> Vec<TermStructure> {
+    let mut families: HashMap<String, TermStructure> = HashMap::new();
+
+    for (i, market) in markets.iter().enumerate() {
+        let prices = &market.outcome_prices;
+        if prices.len() != 2 || prices[0] < 0.01 || prices[1] < 0.01 {
+            continue;
+        }
+
+        let Some(prop) = parser.parse(&market.full_text(), reference(market)) else {
+            continue;
+        };
+        let deadline = match prop.deadline {
+            Some(d) if d.kind == DeadlineKind::By => d.date,
+            // "On" markets check a single date, so later ones don't contain earlier ones
+            Some(_) => continue,
+            None => match reference(market) {
+                Some(date) => date,
+                None => continue,
+            },
+        };
+
+        let threshold = prop.threshold
+            .map(|t| format!("{}{}", t.value, if t.unit == ThresholdUnit::Percent { "%" } else { "" }))
+            .unwrap_or_default();
+        let key = format!("{}|{:?}|{}", prop.subject, prop.comparator, threshold);
+
+        families.entry(key)
+            .or_insert_with(|| TermStructure { subject: prop.subject.clone(), points: Vec::new() })
+            .points.push(TermPoint { market: i, deadline });
+    }
+
+    families.into_values()
+        .filter_map(|mut family| {
+            if family.points.len() > MAX_TERM_POINTS {
+                // Keep the points that can actually be traded
+                family.points.sort_by(|a, b| markets[b.market].liquidity.total_cmp(&markets[a.market].liquidity));
+                family.points.truncate(MAX_TERM_POINTS);
+            }
+            family.points.sort_by_key(|p| p.deadline);
+            (family.deadlines().len() >= 2).then_some(family)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn family(deadlines: &[NaiveDate]) -> TermStructure {
+        TermStructure {
+            subject: "bitcoin".to_string(),
+            points: deadlines.iter().enumerate()
+                .map(|(market, &deadline)| TermPoint { market, deadline })
+                .collect(),
+        }
+    }
+
+    fn markets(yes: &[f64]) -> Vec<Market> {
+        yes.iter().enumerate()
+            .map(|(i, &p)| Market::test("kalshi", &i.to_string(), "", &[p, 1.0 - p]))
+            .collect()
+    }
+
+    #[test]
+    fn one_world_per_window_plus_never() {
+        // Two venues list June 30: they resolve together
+        let f = family(&[date(3, 31), date(6, 30), date(6, 30)]);
+        assert_eq!(f.worlds(), vec![0b000, 0b111, 0b110]);
+    }
+
+    #[test]
+    fn inverted_term_structure_has_negative_hazard() {
+        let f = family(&[date(3, 31), date(6, 30), date(6, 30)]);
+
+        let rates = f.hazard_rates(&markets(&[0.20, 0.40, 0.40]));
+        assert_eq!(rates.len(), 1);
+        let years = 91.0 / 365.0;
+        assert!((rates[0].annual_rate - (0.8f64 / 0.6).ln() / years).abs() < 1e-9);
+
+        // June averages 0.15 across venues, below March
+        let inverted = markets(&[0.20, 0.10, 0.20]);
+        assert!(f.hazard_rates(&inverted)[0].annual_rate < 0.0);
+        assert!(f.describe(&inverted).starts_with("1 inverted window(s), hazard Mar 31→Jun 30 -"), "{}", f.describe(&inverted));
+    }
+
+    #[test]
+    fn assembles_by_deadlines_of_one_proposition() {
+        let questions = [
+            "Will Bitcoin reach $150,000 by March 31?",
+            "Will Bitcoin reach $150,000 by June 30?",
+            "Will Bitcoin reach $200,000 by June 30?",
+            // A single-date check doesn't contain earlier ones
+            "Will BTC be above 150k on June 30?",
+        ];
+        let markets: Vec<Market> = questions.iter().enumerate()
+            .map(|(i, q)| Market::test("polymarket", &i.to_string(), q, &[0.3, 0.7]))
+            .collect();
+        let families = assemble(&PropositionParser::new(), &markets, |_| Some(date(1, 15)));
+
+        assert_eq!(families.len(), 1);
+        let points: Vec<(usize, NaiveDate)> = families[0].points.iter().map(|p| (p.market, p.deadline)).collect();
+        assert_eq!(points, vec![(0, date(3, 31)), (1, date(6, 30))]);
+    }
+}
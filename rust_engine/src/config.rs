@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +18,222 @@ pub struct Config {
     pub scan_interval_seconds: u64,
     #[serde(default = "default_true")]
     pub notifications_enabled: bool,
+    /// Annual cost of capital subtracted from annualized ROI
+    #[serde(default = "default_cost_of_capital")]
+    pub cost_of_capital_percent: f64,
+    /// Minimum annualized net return (after cost of capital)
+    #[serde(default)]
+    pub min_annualized_roi_percent: f64,
+    /// Cash per venue ("Polymarket", "Kalshi", ...); empty = one TOTAL_CAPITAL pool
+    #[serde(default)]
+    pub balances: HashMap<String, f64>,
+    /// Max capital in any one event, as a percent of total capital
+    #[serde(default = "default_event_exposure")]
+    pub max_event_exposure_percent: f64,
+    /// Positions already held; they use up venue cash and event exposure
+    #[serde(default)]
+    pub committed_positions: Vec<CommittedPosition>,
+    /// Consensus/divergence signal over CrossMatcher clusters
+    #[serde(default)]
+    pub divergence: DivergenceConfig,
+    /// Per-strategy sections keyed by `StrategyKind::config_key` (missing = enabled, global thresholds)
+    #[serde(default)]
+    pub strategies: HashMap<String, StrategyConfig>,
+    /// Refetch each opportunity's legs and recheck profit before alerting
+    #[serde(default = "default_true")]
+    pub revalidate_before_alert: bool,
+    /// Thin/stale market filters: a "default" section plus per-venue overrides ("Kalshi", ...)
+    #[serde(default)]
+    pub market_filters: HashMap<String, MarketFilterConfig>,
+    /// When tracked opportunities re-alert and when they count as closed
+    #[serde(default)]
+    pub lifecycle: LifecycleConfig,
+    /// SQLite state store and how long it keeps history
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// Per-market price series recorded every scan
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// CrossMatcher thresholds
+    #[serde(default)]
+    pub matching: MatchingConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchingConfig {
+    /// Drop pairs below this confidence (after the resolution-risk discount)
+    #[serde(default = "default_match_confidence")]
+    pub min_confidence: f64,
+    /// Never pair markets whose close dates are further apart than this
+    #[serde(default = "default_max_close_gap_days")]
+    pub max_close_gap_days: u64,
+}
+
+impl Default for MatchingConfig {
+    fn default() -> Self {
+        Self {
+            min_confidence: default_match_confidence(),
+            max_close_gap_days: default_max_close_gap_days(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_history_path")]
+    pub path: String,
+    /// Keep every point this recent...
+    #[serde(default = "default_full_resolution_hours")]
+    pub full_resolution_hours: f64,
+    /// ...and one per bucket of this many minutes before that
+    #[serde(default = "default_downsample_minutes")]
+    pub downsample_minutes: u32,
+    #[serde(default = "default_history_retention_days")]
+    pub retention_days: u32,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: default_history_path(),
+            full_resolution_hours: default_full_resolution_hours(),
+            downsample_minutes: default_downsample_minutes(),
+            retention_days: default_history_retention_days(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    /// Database file, relative to the working directory
+    #[serde(default = "default_store_path")]
+    pub path: String,
+    #[serde(default = "default_retention_days")]
+    pub alert_retention_days: u32,
+    /// How long closed opportunities stay in the lifecycle table
+    #[serde(default = "default_retention_days")]
+    pub lifecycle_retention_days: u32,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            path: default_store_path(),
+            alert_retention_days: default_retention_days(),
+            lifecycle_retention_days: default_retention_days(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleConfig {
+    /// Re-alert once ROI moves this many percentage points from the last alert
+    #[serde(default = "default_roi_change")]
+    pub min_roi_change_percent: f64,
+    /// Close an opportunity after it is missing from this many consecutive scans
+    #[serde(default = "default_missed_scans")]
+    pub close_after_missed_scans: u32,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        Self {
+            min_roi_change_percent: default_roi_change(),
+            close_after_missed_scans: default_missed_scans(),
+        }
+    }
+}
+
+/// Unset limits don't filter; markets missing a field pass that filter
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketFilterConfig {
+    #[serde(default)]
+    pub min_liquidity: Option<f64>,
+    #[serde(default)]
+    pub min_volume: Option<f64>,
+    /// Max YES bid-ask spread (probability points, e.g. 0.1)
+    #[serde(default)]
+    pub max_spread: Option<f64>,
+    #[serde(default)]
+    pub max_last_trade_age_hours: Option<f64>,
+    #[serde(default)]
+    pub min_top_of_book_size: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DivergenceConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Only cluster CrossMatcher matches at or above this confidence
+    #[serde(default = "default_divergence_confidence")]
+    pub min_confidence: f64,
+    /// Flag a venue this many weighted standard deviations from consensus...
+    #[serde(default = "default_z_score")]
+    pub z_score: f64,
+    /// ...as long as it is also at least this far off (probability points)
+    #[serde(default = "default_min_z_gap")]
+    pub min_z_gap: f64,
+    /// Flag a venue this far from consensus regardless of z-score
+    #[serde(default = "default_divergence_gap")]
+    pub min_gap: f64,
+    /// Liquidity multiplier for play-money venues in the consensus
+    #[serde(default = "default_signal_only_weight")]
+    pub signal_only_weight: f64,
+}
+
+impl Default for DivergenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_confidence: default_divergence_confidence(),
+            z_score: default_z_score(),
+            min_z_gap: default_min_z_gap(),
+            min_gap: default_divergence_gap(),
+            signal_only_weight: default_signal_only_weight(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommittedPosition {
+    pub platform: String,
+    /// Venue event id (Kalshi event ticker, Polymarket event slug)
+    #[serde(default)]
+    pub event: Option<String>,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Overrides the global min_roi_percent
+    #[serde(default)]
+    pub min_roi_percent: Option<f64>,
+    /// Overrides the global min_profit_threshold
+    #[serde(default)]
+    pub min_profit_threshold: Option<f64>,
+    /// Overrides the global min_annualized_roi_percent
+    #[serde(default)]
+    pub min_annualized_roi_percent: Option<f64>,
+    /// Strategy-specific tuning values (e.g. `min_similarity`, `min_gap`)
+    #[serde(flatten)]
+    pub params: HashMap<String, f64>,
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_roi_percent: None,
+            min_profit_threshold: None,
+            min_annualized_roi_percent: None,
+            params: HashMap::new(),
+        }
+    }
 }
 
 fn default_categories() -> Vec<String> {
@@ -29,6 +246,23 @@ fn default_roi() -> f64 { 1.0 }
 fn default_profit() -> f64 { 0.05 }
 fn default_interval() -> u64 { 5 }
 fn default_true() -> bool { true }
+fn default_cost_of_capital() -> f64 { 5.0 }
+fn default_event_exposure() -> f64 { 20.0 }
+fn default_divergence_confidence() -> f64 { 0.7 }
+fn default_z_score() -> f64 { 2.0 }
+fn default_min_z_gap() -> f64 { 0.03 }
+fn default_divergence_gap() -> f64 { 0.10 }
+fn default_signal_only_weight() -> f64 { 0.1 }
+fn default_roi_change() -> f64 { 0.5 }
+fn default_missed_scans() -> u32 { 2 }
+fn default_store_path() -> String { "state.db".to_string() }
+fn default_retention_days() -> u32 { 30 }
+fn default_history_path() -> String { "history.db".to_string() }
+fn default_full_resolution_hours() -> f64 { 24.0 }
+fn default_downsample_minutes() -> u32 { 15 }
+fn default_history_retention_days() -> u32 { 90 }
+fn default_match_confidence() -> f64 { 0.5 }
+fn default_max_close_gap_days() -> u64 { 90 }
 
 impl Config {
     pub fn load() -> Self {
@@ -49,30 +283,45 @@ impl Config {
             min_profit_threshold: default_profit(),
             scan_interval_seconds: default_interval(),
             notifications_enabled: default_true(),
+            cost_of_capital_percent: default_cost_of_capital(),
+            min_annualized_roi_percent: 0.0,
+            balances: HashMap::new(),
+            max_event_exposure_percent: default_event_exposure(),
+            committed_positions: Vec::new(),
+            divergence: DivergenceConfig::default(),
+            strategies: HashMap::new(),
+            revalidate_before_alert: default_true(),
+            market_filters: HashMap::new(),
+            lifecycle: LifecycleConfig::default(),
+            storage: StorageConfig::default(),
+            history: HistoryConfig::default(),
+            matching: MatchingConfig::default(),
         }
     }
     
     pub fn category_keywords(&self) -> Vec<String> {
         let mut keywords = Vec::new();
-        
-        let category_map: std::collections::HashMap<&str, Vec<&str>> = [
-            ("politics", vec!["election", "president", "congress", "senate", "governor", "trump", "biden", "harris", "republican", "democrat"]),
-            ("sports", vec!["nba", "nfl", "mlb", "nhl", "soccer", "football", "basketball", "baseball", "game", "championship"]),
-            ("crypto", vec!["bitcoin", "ethereum", "btc", "eth", "crypto", "blockchain", "defi", "nft"]),
-            ("economics", vec!["fed", "interest rate", "inflation", "gdp", "recession", "stock", "market", "economy"]),
-            ("entertainment", vec!["oscar", "grammy", "movie", "tv", "celebrity", "award"]),
-            ("tech", vec!["ai", "apple", "google", "microsoft", "tesla", "spacex", "technology"]),
-            ("world", vec!["war", "ukraine", "russia", "china", "nato", "un", "world"]),
-        ].iter().cloned().collect();
-        
+
         for cat in &self.enabled_categories {
-            if let Some(kws) = category_map.get(cat.as_str()) {
-                for kw in kws {
-                    keywords.push(kw.to_string());
-                }
+            for kw in keywords_for_category(cat) {
+                keywords.push(kw.to_string());
             }
         }
-        
+
         keywords
     }
 }
+
+/// Keywords that place a market text in one of the config categories
+pub fn keywords_for_category(category: &str) -> &'static [&'static str] {
+    match category {
+        "politics" => &["election", "president", "congress", "senate", "governor", "trump", "biden", "harris", "republican", "democrat"],
+        "sports" => &["nba", "nfl", "mlb", "nhl", "soccer", "football", "basketball", "baseball", "game", "championship"],
+        "crypto" => &["bitcoin", "ethereum", "btc", "eth", "crypto", "blockchain", "defi", "nft"],
+        "economics" => &["fed", "interest rate", "inflation", "gdp", "recession", "stock", "market", "economy"],
+        "entertainment" => &["oscar", "grammy", "movie", "tv", "celebrity", "award"],
+        "tech" => &["ai", "apple", "google", "microsoft", "tesla", "spacex", "technology"],
+        "world" => &["war", "ukraine", "russia", "china", "nato", "un", "world"],
+        _ => &[],
+    }
+}
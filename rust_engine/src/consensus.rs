@@ -0,0 +1,167 @@
+use crate::config::DivergenceConfig;
+use crate::cross_matcher::CrossMatch;
+use crate::engine::Market;
+use serde::Serialize;
+use std::collections::HashMap;
+
+// Keeps zero-liquidity markets in the average without letting them move it
+const MIN_WEIGHT: f64 = 1.0;
+
+/// One venue's price inside a consensus cluster
+#[derive(Debug, Serialize, Clone)]
+pub struct VenuePrice {
+    pub platform: String,
+    pub market_id: String,
+    pub question: String,
+    pub url: String,
+    pub price: f64,
+    pub liquidity: f64,
+    pub tradable: bool,
+    /// Signed distance from consensus
+    pub gap: f64,
+    pub z_score: f64,
+    pub flagged: bool,
+}
+
+/// A cluster of matched markets where at least one venue is off consensus
+#[derive(Debug, Serialize, Clone)]
+pub struct Divergence {
+    pub id: String,
+    pub category: String,
+    /// Liquidity-weighted YES probability across venues
+    pub consensus: f64,
+    pub std_dev: f64,
+    pub venues: Vec<VenuePrice>,
+}
+
+impl Divergence {
+    pub fn flagged(&self) -> impl Iterator<Item = &VenuePrice> {
+        self.venues.iter().filter(|v| v.flagged)
+    }
+}
+
+/// Cluster high-confidence cross matches and flag venues that deviate from consensus
+pub fn find_divergences(matches: &[CrossMatch], markets: &[Market], config: &DivergenceConfig) -> Vec<Divergence> {
+    let by_key: HashMap<String, &Market> = markets.iter().map(|m| (m.key(), m)).collect();
+
+    // Union-find over matched market keys
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut keys: Vec<String> = Vec::new();
+    let mut categories: Vec<String> = Vec::new();
+    let mut parent: Vec<usize> = Vec::new();
+
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+
+    for m in matches.iter().filter(|m| m.confidence >= config.min_confidence) {
+        let key_a = format!("{}:{}", m.platform_a, m.id_a);
+        let key_b = format!("{}:{}", m.platform_b, m.id_b);
+        if !by_key.contains_key(&key_a) || !by_key.contains_key(&key_b) {
+            continue;
+        }
+
+        let mut node = |key: String| -> usize {
+            *index.entry(key.clone()).or_insert_with(|| {
+                keys.push(key);
+                categories.push(m.category.clone());
+                parent.push(parent.len());
+                parent.len() - 1
+            })
+        };
+        let a = node(key_a);
+        let b = node(key_b);
+
+        let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+        if root_a != root_b {
+            parent[root_a] = root_b;
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..keys.len() {
+        let root = find(&mut parent, i);
+        clusters.entry(root).or_default().push(i);
+    }
+
+    let mut divergences = Vec::new();
+    for members in clusters.into_values().filter(|m| m.len() >= 2) {
+        let cluster_markets: Vec<&Market> = members.iter().map(|&i| by_key[&keys[i]]).collect();
+        if let Some(div) = evaluate_cluster(&cluster_markets, &categories[members[0]], config) {
+            divergences.push(div);
+        }
+    }
+
+    // Biggest deviation first
+    divergences.sort_by(|a, b| {
+        let max_gap = |d: &Divergence| d.flagged().map(|v| v.gap.abs()).fold(0.0, f64::max);
+        max_gap(b).partial_cmp(&max_gap(a)).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    divergences
+}
+
+fn evaluate_cluster(markets: &[&Market], category: &str, config: &DivergenceConfig) -> Option<Divergence> {
+    let priced: Vec<(&Market, f64, f64)> = markets.iter()
+        .filter_map(|m| {
+            let price = m.outcome_prices.first().copied()?;
+            if !(0.01..=0.99).contains(&price) {
+                return None;
+            }
+            // Play-money liquidity isn't comparable to dollars
+            let scale = if m.currency.is_tradable() { 1.0 } else { config.signal_only_weight };
+            Some((*m, price, m.liquidity.max(MIN_WEIGHT) * scale))
+        })
+        .collect();
+    if priced.len() < 2 {
+        return None;
+    }
+
+    let total_weight: f64 = priced.iter().map(|(_, _, w)| w).sum();
+    if total_weight <= 0.0 {
+        return None;
+    }
+    let consensus = priced.iter().map(|(_, p, w)| p * w).sum::<f64>() / total_weight;
+    let variance = priced.iter().map(|(_, p, w)| w * (p - consensus).powi(2)).sum::<f64>() / total_weight;
+    let std_dev = variance.sqrt();
+
+    let venues: Vec<VenuePrice> = priced.iter()
+        .map(|(m, price, _)| {
+            let gap = price - consensus;
+            let z_score = if std_dev > 0.0 { gap / std_dev } else { 0.0 };
+            let flagged = gap.abs() >= config.min_gap
+                || (z_score.abs() >= config.z_score && gap.abs() >= config.min_z_gap);
+            VenuePrice {
+                platform: m.platform.clone(),
+                market_id: m.id.clone(),
+                question: m.full_text(),
+                url: m.url.clone().unwrap_or_default(),
+                price: *price,
+                liquidity: m.liquidity,
+                tradable: m.currency.is_tradable(),
+                gap,
+                z_score,
+                flagged,
+            }
+        })
+        .collect();
+
+    if !venues.iter().any(|v| v.flagged) {
+        return None;
+    }
+
+    let mut ids: Vec<String> = priced.iter().map(|(m, _, _)| m.key()).collect();
+    ids.sort();
+
+    Some(Divergence {
+        id: format!("div_{}", ids.join("_")),
+        category: category.to_string(),
+        consensus,
+        std_dev,
+        venues,
+    })
+}
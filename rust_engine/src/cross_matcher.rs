@@ -1,4 +1,6 @@
-use crate::engine::Market;
+use crate::config::MatchingConfig;
+use crate::engine::{CrossExecution, Market};
+use crate::resolution::{ResolutionComparer, ResolutionDiff};
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Utc};
@@ -21,6 +23,18 @@ pub struct CrossMatch {
     pub shared_entities: Vec<String>,
     pub url_a: String,
     pub url_b: String,
+    pub resolution: ResolutionDiff,
+    /// False when either side is play money: a price signal, not a trade
+    pub tradable: bool,
+    /// Costed trade for the pair, filled in by the cross-platform strategy
+    pub execution: Option<CrossExecution>,
+}
+
+impl CrossMatch {
+    /// Market keys ("Kalshi:TICKER") of both sides
+    pub fn market_keys(&self) -> (String, String) {
+        (format!("{}:{}", self.platform_a, self.id_a), format!("{}:{}", self.platform_b, self.id_b))
+    }
 }
 
 // Sports team lists
@@ -46,6 +60,17 @@ const NFL_TEAMS: &[&str] = &[
 
 const SPORTS_CATEGORIES: &[&str] = &["nba", "nfl", "soccer", "mlb"];
 
+// Same-venue duplicates must be near-identical, not just about the same thing
+const DUPLICATE_SIMILARITY: f64 = 0.8;
+const DUPLICATE_MAX_CLOSE_GAP_DAYS: u64 = 7;
+// Buckets larger than this are too generic to compare pairwise
+const MAX_DUPLICATE_BUCKET: usize = 200;
+
+const DUPLICATE_STOP_WORDS: &[&str] = &["the", "will", "and", "for", "before", "after", "with", "this", "that", "than"];
+
+/// Market, its processed text and its word set
+type DuplicateCandidate<'a> = (&'a Market, ProcessedMarket, HashSet<String>);
+
 struct ProcessedMarket {
     text: String,
     entities: HashSet<String>,
@@ -59,10 +84,16 @@ pub struct CrossMatcher {
     categories: Vec<(&'static str, Vec<&'static str>)>,
     year_re: Regex,
     season_re: Regex,
+    number_re: Regex,
+    resolution: ResolutionComparer,
+    min_confidence: f64,
+    max_close_gap_days: u64,
+    /// Log each venue pair as it is matched
+    progress: bool,
 }
 
 impl CrossMatcher {
-    pub fn new() -> Self {
+    pub fn new(config: &MatchingConfig) -> Self {
         let entity_patterns = vec![
             ("trump", Regex::new(r"(?i)\btrump\b").unwrap()),
             ("biden", Regex::new(r"(?i)\bbiden\b").unwrap()),
@@ -106,9 +137,26 @@ impl CrossMatcher {
             categories,
             year_re: Regex::new(r"\b(202[0-9]|203[0-9])\b").unwrap(),
             season_re: Regex::new(r"\b(202[0-9])-(202[0-9])\b").unwrap(),
+            number_re: Regex::new(r"\d+(?:[.,]\d+)*").unwrap(),
+            resolution: ResolutionComparer::new(),
+            min_confidence: config.min_confidence,
+            max_close_gap_days: config.max_close_gap_days,
+            progress: true,
         }
     }
 
+    /// No per-pair console output (replays run many scans)
+    pub fn quiet(mut self) -> Self {
+        self.progress = false;
+        self
+    }
+
+    /// Pick up thresholds from a reloaded config
+    pub fn reconfigure(&mut self, config: &MatchingConfig) {
+        self.min_confidence = config.min_confidence;
+        self.max_close_gap_days = config.max_close_gap_days;
+    }
+
     /// Match markets across all platform pairs
     pub fn match_all(&self, all_markets: &HashMap<String, Vec<&Market>>) -> Vec<CrossMatch> {
         let platforms: Vec<&String> = all_markets.keys().collect();
@@ -121,10 +169,14 @@ impl CrossMatcher {
                 let markets_a = &all_markets[pa];
                 let markets_b = &all_markets[pb];
                 
-                println!("  🔍 {} vs {} ({} x {} markets)...", pa, pb, markets_a.len(), markets_b.len());
-                
+                if self.progress {
+                    println!("  🔍 {} vs {} ({} x {} markets)...", pa, pb, markets_a.len(), markets_b.len());
+                }
+
                 let matches = self.match_pair(markets_a, markets_b);
-                println!("     → {} matches!", matches.len());
+                if self.progress {
+                    println!("     → {} matches!", matches.len());
+                }
                 all_matches.extend(matches);
             }
         }
@@ -134,6 +186,120 @@ impl CrossMatcher {
         all_matches
     }
 
+    /// Near-identical markets listed twice on the same venue (duplicated questions,
+    /// the same event in two series). Confidence is the token similarity,
+    /// discounted by resolution risk like cross-venue matches.
+    pub fn match_duplicates(&self, all_markets: &HashMap<String, Vec<&Market>>) -> Vec<CrossMatch> {
+        let mut all_matches = Vec::new();
+
+        for (platform, markets) in all_markets {
+            // Block on entities + every number + the most specific word, so
+            // "$100k" vs "$120k" or "Lakers" vs "Celtics" never get compared
+            let mut buckets: HashMap<String, Vec<DuplicateCandidate>> = HashMap::new();
+            for m in markets {
+                let proc = self.process(m);
+                let tokens: HashSet<String> = proc.text
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|w| w.len() > 2 && !DUPLICATE_STOP_WORDS.contains(w))
+                    .map(|w| w.to_string())
+                    .collect();
+                if tokens.len() < 3 {
+                    continue;
+                }
+
+                let mut entities: Vec<&String> = proc.entities.iter().collect();
+                entities.sort();
+                let numbers: Vec<&str> = self.number_re.find_iter(&proc.text).map(|n| n.as_str()).collect();
+                let anchor = tokens.iter()
+                    .filter(|t| !t.chars().all(|c| c.is_ascii_digit()))
+                    .max_by(|a, b| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+                    .cloned()
+                    .unwrap_or_default();
+                let key = format!("{:?}|{}|{}", entities, numbers.join(","), anchor);
+
+                buckets.entry(key).or_default().push((*m, proc, tokens));
+            }
+
+            let mut matches = Vec::new();
+            for bucket in buckets.values().filter(|b| b.len() >= 2 && b.len() <= MAX_DUPLICATE_BUCKET) {
+                for i in 0..bucket.len() {
+                    for j in (i + 1)..bucket.len() {
+                        let (raw_a, proc_a, tokens_a) = &bucket[i];
+                        let (raw_b, _, tokens_b) = &bucket[j];
+                        if raw_a.id == raw_b.id {
+                            continue;
+                        }
+
+                        let shared = tokens_a.intersection(tokens_b).count();
+                        let similarity = shared as f64 / (tokens_a.len() + tokens_b.len() - shared) as f64;
+                        if similarity < DUPLICATE_SIMILARITY {
+                            continue;
+                        }
+
+                        if let (Some(ca), Some(cb)) = (raw_a.close_time(), raw_b.close_time()) {
+                            if (ca - cb).num_days().unsigned_abs() > DUPLICATE_MAX_CLOSE_GAP_DAYS {
+                                continue; // Same wording, different period
+                            }
+                        }
+
+                        let resolution = self.resolution.compare(raw_a, raw_b);
+                        let confidence = similarity * (1.0 - resolution.risk);
+                        if confidence < self.min_confidence {
+                            continue;
+                        }
+
+                        let category = proc_a.category.clone().unwrap_or_else(|| "duplicate".to_string());
+                        matches.push(self.build_match(raw_a, raw_b, confidence, category, Vec::new(), resolution));
+                    }
+                }
+            }
+
+            if self.progress {
+                println!("  🔍 {} duplicates: {} pairs", platform, matches.len());
+            }
+            all_matches.extend(matches);
+        }
+
+        all_matches
+    }
+
+    fn build_match(
+        &self,
+        raw_a: &Market,
+        raw_b: &Market,
+        confidence: f64,
+        category: String,
+        shared_entities: Vec<String>,
+        resolution: ResolutionDiff,
+    ) -> CrossMatch {
+        let yes_a = raw_a.outcome_prices.first().copied().unwrap_or(0.0);
+        let yes_b = raw_b.outcome_prices.first().copied().unwrap_or(0.0);
+        let price_diff = (yes_a - yes_b).abs();
+
+        let q_a = self.get_question(raw_a);
+        let q_b = self.get_question(raw_b);
+
+        CrossMatch {
+            platform_a: raw_a.platform.clone(),
+            platform_b: raw_b.platform.clone(),
+            id_a: raw_a.id.clone(),
+            id_b: raw_b.id.clone(),
+            question_a: q_a.chars().take(100).collect(),
+            question_b: q_b.chars().take(100).collect(),
+            yes_price_a: yes_a,
+            yes_price_b: yes_b,
+            price_diff: (price_diff * 10000.0).round() / 10000.0,
+            confidence,
+            category,
+            shared_entities,
+            url_a: raw_a.url.clone().unwrap_or_default(),
+            url_b: raw_b.url.clone().unwrap_or_default(),
+            resolution,
+            tradable: raw_a.currency.is_tradable() && raw_b.currency.is_tradable(),
+            execution: None,
+        }
+    }
+
     fn match_pair(&self, markets_a: &[&Market], markets_b: &[&Market]) -> Vec<CrossMatch> {
         // Pre-process all markets
         let processed_a: Vec<(&Market, ProcessedMarket)> = markets_a.iter()
@@ -176,20 +342,19 @@ impl CrossMatcher {
                 // STRICT YEAR CHECK
                 let years_a = self.extract_years(&proc_a.text);
                 let years_b = self.extract_years(&proc_b.text);
-                if !years_a.is_empty() && !years_b.is_empty() {
-                    if years_a.intersection(&years_b).count() == 0 {
-                        continue; // Different years
-                    }
+                if !years_a.is_empty() && !years_b.is_empty()
+                    && years_a.intersection(&years_b).count() == 0 {
+                    continue; // Different years
                 }
 
-                // CLOSE DATE CHECK: within 90 days
+                // CLOSE DATE CHECK: within max_close_gap_days
                 if let (Some(ref cd_a), Some(ref cd_b)) = (&raw_a.close_date, &raw_b.close_date) {
                     if let (Ok(da), Ok(db)) = (
                         cd_a.parse::<DateTime<Utc>>(),
                         cd_b.parse::<DateTime<Utc>>(),
                     ) {
                         let gap_days = (da - db).num_days().unsigned_abs();
-                        if gap_days > 90 {
+                        if gap_days > self.max_close_gap_days {
                             continue; // Too far apart
                         }
                     }
@@ -218,33 +383,22 @@ impl CrossMatcher {
                 }
                 confidence = confidence.min(1.0);
 
-                if confidence < 0.5 {
+                // Discount by how differently the two venues resolve
+                let resolution = self.resolution.compare(raw_a, raw_b);
+                confidence *= 1.0 - resolution.risk;
+
+                if confidence < self.min_confidence {
                     continue;
                 }
 
-                let yes_a = raw_a.outcome_prices.first().copied().unwrap_or(0.0);
-                let yes_b = raw_b.outcome_prices.first().copied().unwrap_or(0.0);
-                let price_diff = (yes_a - yes_b).abs();
-
-                let q_a = self.get_question(raw_a);
-                let q_b = self.get_question(raw_b);
-
-                matches.push(CrossMatch {
-                    platform_a: raw_a.platform.clone(),
-                    platform_b: raw_b.platform.clone(),
-                    id_a: raw_a.id.clone(),
-                    id_b: raw_b.id.clone(),
-                    question_a: if q_a.len() > 100 { q_a[..100].to_string() } else { q_a },
-                    question_b: if q_b.len() > 100 { q_b[..100].to_string() } else { q_b },
-                    yes_price_a: yes_a,
-                    yes_price_b: yes_b,
-                    price_diff: (price_diff * 10000.0).round() / 10000.0,
+                matches.push(self.build_match(
+                    raw_a,
+                    raw_b,
                     confidence,
-                    category: cat_a.clone(),
-                    shared_entities: shared.into_iter().cloned().collect(),
-                    url_a: raw_a.url.clone().unwrap_or_default(),
-                    url_b: raw_b.url.clone().unwrap_or_default(),
-                });
+                    cat_a.clone(),
+                    shared.into_iter().cloned().collect(),
+                    resolution,
+                ));
             }
         }
 
@@ -330,3 +484,44 @@ impl CrossMatcher {
         String::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher() -> CrossMatcher {
+        CrossMatcher::new(&MatchingConfig { min_confidence: 0.5, max_close_gap_days: 90 }).quiet()
+    }
+
+    fn duplicates(a: &Market, b: &Market) -> Vec<CrossMatch> {
+        let markets = HashMap::from([("Kalshi".to_string(), vec![a, b])]);
+        matcher().match_duplicates(&markets)
+    }
+
+    #[test]
+    fn duplicate_confidence_is_discounted_by_resolution_risk() {
+        let question = "Will the Federal Reserve cut interest rates at the December meeting?";
+        let mut a = Market::test("Kalshi", "FED-A", question, &[0.40, 0.60]);
+        let mut b = Market::test("Kalshi", "FED-B", question, &[0.45, 0.55]);
+        a.rules = Some("Resolves per the FOMC statement.".to_string());
+        b.rules = Some("Resolves per the FOMC statement.".to_string());
+
+        let same_rules = duplicates(&a, &b);
+        assert_eq!(same_rules.len(), 1);
+        assert!((same_rules[0].confidence - 1.0).abs() < 1e-9);
+
+        // Different sources and one-sided postponement and cancellation clauses push it under the cutoff
+        b.rules = Some("Resolves per Bloomberg; if the meeting is postponed or cancelled it resolves No.".to_string());
+        assert!(duplicates(&a, &b).is_empty());
+    }
+
+    #[test]
+    fn long_non_ascii_questions_truncate_safely() {
+        let question = format!("Will “{}” win the Ballon d’Or?", "Pelé ".repeat(30));
+        let a = Market::test("Kalshi", "A", &question, &[0.4, 0.6]);
+        let b = Market::test("Kalshi", "B", &question, &[0.5, 0.5]);
+        let matches = duplicates(&a, &b);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].question_a.chars().count(), 100);
+    }
+}
@@ -1,64 +1,46 @@
 use serde::{Deserialize, Serialize};
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use crate::lp_solver::{self, LogicalConstraint, LpMarket, LpSolution, Side, MAX_LP_MARKETS};
+use crate::proposition::{Compound, Connective, Proposition, PropositionParser};
+use crate::rules::{Relation, RuleSet};
+use crate::resolution::{ResolutionComparer, ResolutionDiff};
+use crate::strategy::{self, ScanContext, Strategy, StrategySettings, StrategyStats};
+use crate::config::Config;
+use crate::cross_matcher::CrossMatch;
+use crate::ladder::{self, Ladder};
+use crate::calendar::{self, TermStructure};
+use chrono::{DateTime, NaiveDate, Utc};
 
 // Platform fee constants (percentage)
 const POLYMARKET_FEE: f64 = 0.02; // 2%
 const KALSHI_FEE: f64 = 0.01;     // 1%
-const MANIFOLD_FEE: f64 = 0.02;   // 2%
-
-// Minimum profit threshold from Roan's research ($0.05)
-const MIN_PROFIT_THRESHOLD: f64 = 0.05;
-
-// Implication patterns for dependency detection
-// Format: (keyword_a, keyword_b) means if market contains A, it implies market B
-const IMPLICATION_PATTERNS: &[(&str, &str)] = &[
-    // === POLITICS ===
-    ("trump win", "republican win"),
-    ("trump wins", "republicans win"),
-    ("biden win", "democrat win"),
-    ("harris win", "democrat win"),
-    ("landslide", "win"),
-    ("win by 5+", "win"),
-    ("win by 10+", "win by 5+"),
-    // === CRYPTO ===
-    ("bitcoin 200k", "bitcoin 150k"),
-    ("bitcoin 150k", "bitcoin 100k"),
-    ("bitcoin 100k", "bitcoin 75k"),
-    ("ethereum 10k", "ethereum 5k"),
-    ("btc 200k", "btc 100k"),
-    ("btc 100k", "btc 75k"),
-    ("eth 10k", "eth 5k"),
-    ("solana 500", "solana 300"),
-    ("solana 300", "solana 200"),
-    // === ECONOMICS ===
-    ("recession 2025", "gdp negative"),
-    ("fed cut", "rate decrease"),
-    ("fed cuts 3", "fed cuts 2"),
-    ("fed cuts 2", "fed cut"),
-    ("inflation below 2", "inflation below 3"),
-    ("unemployment above 5", "unemployment above 4"),
-    // === SPORTS ===
-    ("sweep", "win series"),
-    ("win in 4", "win series"),
-    ("win in 5", "win series"),
-    ("win finals", "reach finals"),
-    ("win championship", "reach playoffs"),
-    ("super bowl win", "reach super bowl"),
-    ("win mvp", "reach playoffs"),
-    // === AI & TECH ===
-    ("agi by 2026", "agi by 2030"),
-    ("agi by 2027", "agi by 2030"),
-    ("tesla 500", "tesla 400"),
-    ("tesla 400", "tesla 300"),
-    ("nvidia 200", "nvidia 150"),
-    ("apple 250", "apple 200"),
-    // === GENERAL ===
-    ("before march", "in 2025"),
-    ("before june", "in 2025"),
-    ("by q1", "in 2025"),
-    ("by q2", "by end of year"),
-];
+const MANIFOLD_FEE: f64 = 0.02;   // 2% (sweepcash only, mana markets never trade)
+
+const MIN_DAYS_TO_RESOLUTION: f64 = 1.0;
+/// Horizon assumed for opportunities with no close date, so they are annualized
+/// (and filtered) conservatively instead of skipping the annualized floor
+pub const UNDATED_HORIZON_DAYS: f64 = 365.0;
+
+/// What a market's shares are denominated in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Currency {
+    #[default]
+    Usd,
+    Usdc,
+    /// Manifold play money
+    Mana,
+    /// Manifold real-money (sweepstakes) markets
+    Sweepcash,
+}
+
+impl Currency {
+    /// Whether a position can be turned into dollars; other markets are signal only
+    pub fn is_tradable(&self) -> bool {
+        !matches!(self, Currency::Mana)
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Market {
@@ -71,49 +53,391 @@ pub struct Market {
     pub liquidity: f64,
     pub close_date: Option<String>,
     pub url: Option<String>,
+    /// Outcome labels, parallel to `outcome_prices` (["Yes", "No"] for binary markets)
+    pub outcomes: Vec<String>,
+    /// Venue resolution rules (Kalshi rules_primary/secondary, Polymarket description)
+    pub rules: Option<String>,
+    pub resolution_source: Option<String>,
+    /// Venue event grouping (Kalshi event ticker, Polymarket event slug)
+    pub event_id: Option<String>,
+    pub currency: Currency,
+    /// Traded volume in venue units (contracts on Kalshi, USD on Polymarket)
+    #[serde(default)]
+    pub volume: Option<f64>,
+    /// Best YES ask minus best YES bid
+    #[serde(default)]
+    pub spread: Option<f64>,
+    /// Best YES bid and ask (probability), where the venue quotes a book
+    #[serde(default)]
+    pub best_bid: Option<f64>,
+    #[serde(default)]
+    pub best_ask: Option<f64>,
+    #[serde(default)]
+    pub last_trade_time: Option<DateTime<Utc>>,
+    /// Shares at the best ask; `Some(0.0)` when a side has no quotes at all
+    #[serde(default)]
+    pub top_of_book_size: Option<f64>,
+    /// When our fetch received this market
+    #[serde(default)]
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// The venue's own last-update timestamp, when it reports one
+    #[serde(default)]
+    pub venue_time: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[cfg(test)]
+impl Market {
+    /// A liquid binary market with the given YES/NO prices
+    pub(crate) fn test(platform: &str, id: &str, question: &str, prices: &[f64]) -> Self {
+        Market {
+            id: id.to_string(),
+            question: Some(question.to_string()),
+            title: None,
+            subtitle: None,
+            outcome_prices: prices.to_vec(),
+            platform: platform.to_string(),
+            liquidity: 10_000.0,
+            close_date: None,
+            url: None,
+            outcomes: if prices.len() == 2 {
+                vec!["Yes".to_string(), "No".to_string()]
+            } else {
+                (1..=prices.len()).map(|i| format!("Outcome {}", i)).collect()
+            },
+            rules: None,
+            resolution_source: None,
+            event_id: None,
+            currency: Currency::Usd,
+            volume: None,
+            spread: None,
+            best_bid: None,
+            best_ask: None,
+            last_trade_time: None,
+            top_of_book_size: None,
+            fetched_at: None,
+            venue_time: None,
+        }
+    }
+}
+
+impl Market {
+    /// Parsed close date; bare dates close at the end of that day (UTC)
+    pub fn close_time(&self) -> Option<DateTime<Utc>> {
+        let cd = self.close_date.as_deref()?;
+        cd.parse::<DateTime<Utc>>().ok().or_else(|| {
+            NaiveDate::parse_from_str(cd, "%Y-%m-%d").ok()
+                .and_then(|d| d.and_hms_opt(23, 59, 59))
+                .map(|dt| dt.and_utc())
+        })
+    }
+
+    /// Unique key across venues
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.platform, self.id)
+    }
+
+    /// Exposure bucket: the venue event, or the market itself when it has none
+    pub fn event_key(&self) -> String {
+        match &self.event_id {
+            Some(event) => format!("{}:{}", self.platform, event),
+            None => self.key(),
+        }
+    }
+
+    /// Question, title and subtitle joined for keyword matching
+    pub fn full_text(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.question.clone().unwrap_or_default(),
+            self.title.clone().unwrap_or_default(),
+            self.subtitle.clone().unwrap_or_default()
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StrategyKind {
+    SinglePlatform,
+    CrossPlatform,
+    IntraVenue,
+    Combinatorial,
+    Exclusion,
+    MultiCondition,
+    LpBasket,
+    Ladder,
+    Calendar,
+}
+
+impl StrategyKind {
+    /// Section name under `strategies` in config.json
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            StrategyKind::SinglePlatform => "single_platform",
+            StrategyKind::CrossPlatform => "cross_platform",
+            StrategyKind::IntraVenue => "intra_venue",
+            StrategyKind::Combinatorial => "combinatorial",
+            StrategyKind::Exclusion => "exclusion",
+            StrategyKind::MultiCondition => "multi_condition",
+            StrategyKind::LpBasket => "lp_basket",
+            StrategyKind::Ladder => "ladder",
+            StrategyKind::Calendar => "calendar",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StrategyKind::SinglePlatform => "Single-Platform",
+            StrategyKind::CrossPlatform => "Cross-Platform",
+            StrategyKind::IntraVenue => "Intra-Venue",
+            StrategyKind::Combinatorial => "Combinatorial",
+            StrategyKind::Exclusion => "Exclusion",
+            StrategyKind::MultiCondition => "Multi-Condition",
+            StrategyKind::LpBasket => "LP-Basket",
+            StrategyKind::Ladder => "Ladder",
+            StrategyKind::Calendar => "Calendar",
+        }
+    }
+}
+
+/// One order in an opportunity: buy `size` shares of `side` on `outcome` at up to `limit_price`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Leg {
+    pub market_key: String,
+    pub event_key: String,
+    pub platform: String,
+    pub question: String,
+    pub url: String,
+    pub side: Side,
+    pub outcome: String,
+    /// Index into the market's `outcome_prices`
+    pub outcome_index: usize,
+    pub limit_price: f64,
+    pub size: f64,
+    pub fee: f64,
+    pub close_time: Option<DateTime<Utc>>,
+    /// Fetch and venue timestamps of the price this leg was built from
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub venue_time: Option<DateTime<Utc>>,
+}
+
+impl Leg {
+    pub fn cost(&self) -> f64 {
+        self.limit_price * self.size
+    }
+
+    /// Current price of this leg in a freshly fetched copy of its market
+    pub fn price_in(&self, market: &Market) -> Option<f64> {
+        let p = market.outcome_prices.get(self.outcome_index).copied()?;
+        match (market.outcome_prices.len(), self.side) {
+            // Binary markets list both sides; multi-outcome NO is the complement
+            (2, _) | (_, Side::Yes) => Some(p),
+            (_, Side::No) => Some(1.0 - p),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let side = match self.side {
+            Side::Yes => "YES",
+            Side::No => "NO",
+        };
+        let size = if (self.size - 1.0).abs() > 1e-9 {
+            format!("{:.2}x ", self.size)
+        } else {
+            String::new()
+        };
+        // Binary markets are named by their question, multi-outcome legs by the outcome
+        let is_binary_label = self.outcome.eq_ignore_ascii_case("yes") || self.outcome.eq_ignore_ascii_case("no");
+        let label = if is_binary_label { &self.question } else { &self.outcome };
+        let label: String = if label.chars().count() > 30 {
+            format!("{}...", label.chars().take(30).collect::<String>())
+        } else {
+            label.clone()
+        };
+        format!("Buy {}{} '{}' @${:.2} on {}", size, side, label, self.limit_price, self.platform)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Opportunity {
     pub id: String,
-    pub opp_type: String,
+    pub kind: StrategyKind,
     pub description: String,
-    pub market_a: String,
-    pub market_b: String,
-    pub platform_a: String,
-    pub platform_b: String,
-    pub url_a: String,
-    pub url_b: String,
-    pub buy_yes_price: f64,
-    pub buy_no_price: f64,
+    pub legs: Vec<Leg>,
     pub total_cost: f64,
     pub gross_profit: f64,
     pub net_profit_after_fees: f64,
     pub roi_percent: f64,
     pub suggested_position: f64,
-    pub action: String,
+    /// Days until the last leg resolves (capital is locked until then)
+    pub days_to_resolution: Option<f64>,
+    /// ROI scaled to a year, minus the cost of capital; undated opportunities
+    /// are annualized over `UNDATED_HORIZON_DAYS`
+    pub annualized_roi_percent: Option<f64>,
+    /// Chance the legs really pay out as modeled (1.0 for same-contract strategies)
+    pub confidence: f64,
+    /// Rules diff for cross-venue pairs
+    pub resolution: Option<ResolutionDiff>,
+    /// Constraint that capped `suggested_position` below the Kelly target, if any
+    pub sizing_limit: Option<String>,
+    /// Set once the legs were refetched and the profit still held
+    pub confirmation: Option<Confirmation>,
+    pub timing: Timing,
+}
+
+#[cfg(test)]
+impl Opportunity {
+    /// A legless opportunity with the given ROI
+    pub(crate) fn test(id: &str, roi_percent: f64) -> Self {
+        Opportunity {
+            id: id.to_string(),
+            kind: StrategyKind::Combinatorial,
+            description: String::new(),
+            legs: Vec::new(),
+            total_cost: 1.0,
+            gross_profit: roi_percent / 100.0,
+            net_profit_after_fees: roi_percent / 100.0,
+            roi_percent,
+            suggested_position: 0.0,
+            days_to_resolution: None,
+            annualized_roi_percent: None,
+            confidence: 1.0,
+            resolution: None,
+            sizing_limit: None,
+            confirmation: None,
+            timing: Timing::default(),
+        }
+    }
+}
+
+/// Where the time went between venue data and alert, in milliseconds
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Timing {
+    /// Oldest leg fetch to the end of the strategy that found this
+    pub data_age_ms: Option<i64>,
+    /// Oldest leg venue timestamp to the end of the strategy that found this
+    pub venue_age_ms: Option<i64>,
+    /// Start of analysis to the end of the strategy that found this
+    pub analysis_ms: u128,
+    /// Duration of the alert request
+    pub send_ms: Option<u128>,
+    /// Oldest leg data (venue timestamp, else fetch) to alert delivered
+    pub end_to_end_ms: Option<i64>,
+}
+
+/// Result of refetching an opportunity's legs just before alerting
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Confirmation {
+    /// Batch snapshot to confirmed prices
+    pub snapshot_to_confirm_ms: u128,
+    /// Time spent refetching the legs
+    pub refetch_ms: u128,
+    /// Net profit as seen in the snapshot
+    pub snapshot_net_profit: f64,
+}
+
+impl Opportunity {
+    /// Oldest data behind the legs: the venue timestamp where known, else the fetch time
+    pub fn oldest_data(&self) -> Option<DateTime<Utc>> {
+        self.legs.iter().filter_map(|l| l.venue_time.or(l.fetched_at)).min()
+    }
+
+    /// Latest close across the legs; unknown if any leg has no close date
+    pub fn resolution_time(&self) -> Option<DateTime<Utc>> {
+        let times: Option<Vec<DateTime<Utc>>> = self.legs.iter().map(|l| l.close_time).collect();
+        times?.into_iter().max()
+    }
+
+    pub fn total_fees(&self) -> f64 {
+        self.legs.iter().map(|l| l.fee).sum()
+    }
+
+    /// Venues involved, in leg order
+    pub fn platforms(&self) -> Vec<&str> {
+        let mut platforms: Vec<&str> = Vec::new();
+        for leg in &self.legs {
+            if !platforms.contains(&leg.platform.as_str()) {
+                platforms.push(&leg.platform);
+            }
+        }
+        platforms
+    }
+}
+
+/// Outcome of costing a CrossMatcher match as an executable trade
+#[derive(Debug, Serialize, Clone)]
+pub enum CrossExecution {
+    /// Buy YES on one venue and NO on the other for a locked-in profit
+    Arb(Box<Opportunity>),
+    /// Priced, but fees (or the strategy thresholds) eat the spread
+    NoArbAfterFees { total_cost: f64, fees: f64, net_profit: f64 },
+    /// Can't be traded as a pair (play money, missing prices, rules diverge)
+    NotExecutable { reason: String },
+}
+
+/// Result of one `analyze_markets` pass
+pub struct ScanReport {
+    pub opportunities: Vec<Opportunity>,
+    pub dependency_ms: u128,
+    pub strategies: Vec<StrategyStats>,
 }
 
 // Logical dependency between markets
 #[derive(Debug, Clone)]
-struct MarketDependency {
+pub struct MarketDependency {
     implied_market: usize,   // Index of market that is implied
     implying_market: usize,  // Index of market that implies
-    dependency_type: String, // "implies" or "mutually_exclusive"
+    dependency_type: String, // "implies", "equivalent", "mutually_exclusive", "exhaustive",
+                             // "conjunction" (A∧B => A) or "disjunction" (A => A∨B)
+}
+
+impl MarketDependency {
+    /// Dependencies where YES on the implying market forces YES on the implied one
+    fn is_implication(&self) -> bool {
+        matches!(self.dependency_type.as_str(), "implies" | "equivalent" | "conjunction" | "disjunction")
+    }
+
+    /// Translate into an LP constraint using solver-local indices
+    fn to_constraint(&self, implying: usize, implied: usize) -> Option<LogicalConstraint> {
+        match self.dependency_type.as_str() {
+            "implies" | "equivalent" | "conjunction" | "disjunction" => Some(LogicalConstraint::Implies(implying, implied)),
+            "mutually_exclusive" => Some(LogicalConstraint::MutuallyExclusive(implying, implied)),
+            "exhaustive" => Some(LogicalConstraint::Exhaustive(vec![implying, implied])),
+            _ => None,
+        }
+    }
 }
 
 pub struct ArbitrageEngine {
-    pub min_roi: f64,
-    pub min_profit_threshold: f64,
     pub total_capital: f64,
+    /// Annual rate charged for locking capital until resolution (fraction)
+    pub cost_of_capital: f64,
+    /// Time that days-to-resolution count from; None = the wall clock (backtests set the snapshot time)
+    pub as_of: Option<DateTime<Utc>>,
+    parser: PropositionParser,
+    rules: RuleSet,
+    resolution: ResolutionComparer,
+    /// Enabled strategies in run order, with their resolved thresholds
+    strategies: Vec<(Box<dyn Strategy>, StrategySettings)>,
 }
 
 impl ArbitrageEngine {
-    pub fn new(min_roi: f64, min_profit_threshold: f64, total_capital: f64) -> Self {
+    pub fn new(config: &Config, total_capital: f64, rules: RuleSet) -> Self {
+        let strategies = strategy::registry().into_iter()
+            .map(|s| {
+                let settings = StrategySettings::from_config(config, s.kind());
+                (s, settings)
+            })
+            .filter(|(_, settings)| settings.enabled)
+            .collect();
+
         Self {
-            min_roi,
-            min_profit_threshold: min_profit_threshold.max(MIN_PROFIT_THRESHOLD), // At least $0.05
             total_capital,
+            cost_of_capital: config.cost_of_capital_percent / 100.0,
+            as_of: None,
+            parser: PropositionParser::new(),
+            rules,
+            resolution: ResolutionComparer::new(),
+            strategies,
         }
     }
 
@@ -126,45 +450,85 @@ impl ArbitrageEngine {
         }
     }
 
-    pub fn analyze_markets(&self, markets: &[Market]) -> Vec<Opportunity> {
-        let mut opportunities = Vec::new();
-
-        // 1. Single-platform arbitrage (YES + NO < 1.0)
-        let single_opps: Vec<Opportunity> = markets.par_iter()
-            .filter_map(|m| self.check_single_platform(m))
-            .collect();
-        opportunities.extend(single_opps);
-
-        // 2. Cross-platform arbitrage
-        let cross_opps = self.check_cross_platform(markets);
-        opportunities.extend(cross_opps);
+    pub fn analyze_markets(&self, markets: &[Market], cross_matches: &mut [CrossMatch]) -> ScanReport {
+        let analysis_start = Instant::now();
 
-        // 3. COMBINATORIAL ARBITRAGE (Roan's method)
-        // Detect logical dependencies and exploit price inconsistencies
-        let combinatorial_opps = self.check_combinatorial_arbitrage(markets);
-        opportunities.extend(combinatorial_opps);
+        // Strategies that annotate the cross matches see every market, play money included
+        let mut prepare_ms: HashMap<StrategyKind, u128> = HashMap::new();
+        for (strategy, settings) in &self.strategies {
+            let start = Instant::now();
+            strategy.prepare(self, markets, cross_matches, settings);
+            prepare_ms.insert(strategy.kind(), start.elapsed().as_millis());
+        }
+        let cross_matches: &[CrossMatch] = cross_matches;
 
-        // 4. Multi-condition market rebalancing
-        let rebalance_opps = self.check_multi_condition_rebalancing(markets);
+        // Play-money markets can't be part of an executable arb; they only feed signals
+        let tradable: Vec<Market> = markets.iter()
+            .filter(|m| m.currency.is_tradable())
+            .cloned()
+            .collect();
+        let markets = tradable.as_slice();
 
+        // Dependency detection is shared, so only pay for it when a strategy needs it
+        let dependency_start = Instant::now();
+        let dependencies = if self.strategies.iter().any(|(s, _)| s.uses_dependencies()) {
+            self.detect_dependencies(markets)
+        } else {
+            Vec::new()
+        };
+        let dependency_ms = dependency_start.elapsed().as_millis();
 
-        opportunities.extend(rebalance_opps);
+        let ctx = ScanContext { markets, dependencies: &dependencies, cross_matches };
+        let mut opportunities = Vec::new();
+        let mut stats = Vec::new();
+
+        for (strategy, settings) in &self.strategies {
+            let start = Instant::now();
+            let now = self.as_of.unwrap_or_else(Utc::now);
+            let found: Vec<Opportunity> = strategy.run(self, &ctx, settings).into_iter()
+                .map(|mut opp| {
+                    self.apply_time_value(&mut opp, now);
+                    opp
+                })
+                .filter(|opp| opp.annualized_roi_percent
+                    .is_some_and(|annualized| annualized >= settings.min_annualized_roi * 100.0))
+                .map(|mut opp| {
+                    let done = Utc::now();
+                    let age = |t: Option<DateTime<Utc>>| t.map(|t| (done - t).num_milliseconds());
+                    opp.timing.analysis_ms = analysis_start.elapsed().as_millis();
+                    opp.timing.data_age_ms = age(opp.legs.iter().filter_map(|l| l.fetched_at).min());
+                    opp.timing.venue_age_ms = age(opp.legs.iter().filter_map(|l| l.venue_time).min());
+                    opp
+                })
+                .collect();
+            stats.push(StrategyStats {
+                kind: strategy.kind(),
+                elapsed_ms: start.elapsed().as_millis() + prepare_ms.get(&strategy.kind()).copied().unwrap_or(0),
+                opportunities: found.len(),
+            });
+            opportunities.extend(found);
+        }
 
-        // Sort by profit (highest first)
+        // Rank by annualized net return (undated ones over the assumed horizon), then by profit
         opportunities.sort_by(|a, b| {
-            b.net_profit_after_fees.partial_cmp(&a.net_profit_after_fees)
-                .unwrap_or(std::cmp::Ordering::Equal)
+            let annualized = |o: &Opportunity| o.annualized_roi_percent.unwrap_or(f64::NEG_INFINITY);
+            annualized(b).total_cmp(&annualized(a))
+                .then(b.net_profit_after_fees.total_cmp(&a.net_profit_after_fees))
         });
 
-        opportunities
+        ScanReport {
+            opportunities,
+            dependency_ms,
+            strategies: stats,
+        }
     }
 
-    fn check_single_platform(&self, market: &Market) -> Option<Opportunity> {
+    pub(crate) fn check_single_platform(&self, market: &Market, settings: &StrategySettings) -> Option<Opportunity> {
         if market.outcome_prices.len() < 2 {
             return None;
         }
 
-        let yes_price = market.outcome_prices.get(0).copied().unwrap_or(0.0);
+        let yes_price = market.outcome_prices.first().copied().unwrap_or(0.0);
         let no_price = market.outcome_prices.get(1).copied().unwrap_or(0.0);
 
         // Skip markets with unreliable prices
@@ -181,32 +545,39 @@ impl ArbitrageEngine {
             let total_fees = total_cost * fee * 2.0;
             let net_profit = gross_profit - total_fees;
 
-            if net_profit >= self.min_profit_threshold {
+            if net_profit >= settings.min_profit {
                 let roi = (net_profit / total_cost) * 100.0;
-                if roi >= self.min_roi * 100.0 {
+                if roi >= settings.min_roi * 100.0 {
                     let position_size = self.calculate_position_size(net_profit, total_cost);
-                    
+
+                    let mut legs = vec![
+                        self.leg(market, Side::Yes, 0, yes_price, 1.0),
+                        self.leg(market, Side::No, 1, no_price, 1.0),
+                    ];
+                    // Single-platform charges the venue fee twice per leg (matches total_fees)
+                    for leg in &mut legs {
+                        leg.fee *= 2.0;
+                    }
+
                     return Some(Opportunity {
                         id: format!("single_{}", market.id),
-                        opp_type: "Single-Platform".into(),
+                        kind: StrategyKind::SinglePlatform,
                         description: market.question.clone()
                             .or(market.title.clone())
                             .unwrap_or_default(),
-                        market_a: market.id.clone(),
-                        market_b: market.id.clone(),
-                        platform_a: market.platform.clone(),
-                        platform_b: market.platform.clone(),
-                        url_a: market.url.clone().unwrap_or_default(),
-                        url_b: market.url.clone().unwrap_or_default(),
-                        buy_yes_price: yes_price,
-                        buy_no_price: no_price,
+                        legs,
                         total_cost,
                         gross_profit,
                         net_profit_after_fees: net_profit,
                         roi_percent: roi,
                         suggested_position: position_size,
-                        action: format!("Buy YES @${:.2} + NO @${:.2} on {}", 
-                            yes_price, no_price, market.platform),
+                        days_to_resolution: None,
+                        annualized_roi_percent: None,
+                        confidence: 1.0,
+                        resolution: None,
+                        sizing_limit: None,
+                        confirmation: None,
+                        timing: Timing::default(),
                     });
                 }
             }
@@ -214,97 +585,42 @@ impl ArbitrageEngine {
         None
     }
 
-    fn check_cross_platform(&self, markets: &[Market]) -> Vec<Opportunity> {
-        let mut opportunities = Vec::new();
-
-        let stop_words: HashSet<&str> = ["the", "a", "an", "is", "will", "be", "to", "of", "in", "for", "on", "at", "by"].iter().cloned().collect();
-
-        let polymarket: Vec<&Market> = markets.iter()
-            .filter(|m| m.platform == "Polymarket")
-            .collect();
-        let kalshi: Vec<&Market> = markets.iter()
-            .filter(|m| m.platform == "Kalshi")
-            .collect();
-
-        // optimization: Pre-compute lowercase text AND word sets to avoid re-allocation in O(N*M) loop
-        let poly_data: Vec<(&Market, String, HashSet<String>)> = polymarket.par_iter()
-            .map(|m| {
-                let text = self.get_market_text(m).to_lowercase();
-                let words: HashSet<String> = text.split_whitespace()
-                    .filter(|w| !stop_words.contains(w) && w.len() > 2)
-                    .map(|w| w.to_string())
-                    .collect();
-                (*m, text, words)
-            })
-            .collect();
-
-        let kalshi_data: Vec<(&Market, String, HashSet<String>)> = kalshi.par_iter()
-            .map(|m| {
-                let text = self.get_market_text(m).to_lowercase();
-                let words: HashSet<String> = text.split_whitespace()
-                    .filter(|w| !stop_words.contains(w) && w.len() > 2)
-                    .map(|w| w.to_string())
-                    .collect();
-                (*m, text, words)
-            })
-            .collect();
-
-        // Parallelize the N*M comparison
-        let cross_opps: Vec<Opportunity> = poly_data.par_iter()
-            .flat_map(|(poly_market, poly_text, poly_words)| {
-                let mut local_opps = Vec::new();
-                if poly_words.is_empty() { return local_opps; }
-
-                for (kalshi_market, kalshi_text, kalshi_words) in &kalshi_data {
-                    if kalshi_words.is_empty() { continue; }
-
-                    // Optimization: Check if length difference is too big (strings can't be similar)
-                    if (poly_text.len() as i32 - kalshi_text.len() as i32).abs() > 60 {
-                        continue;
-                    }
-
-                    let similarity = self.calculate_similarity_sets(poly_words, kalshi_words);
-                    
-                    if similarity > 0.4 {
-                        if let Some(opp) = self.calculate_cross_platform_spread(poly_market, kalshi_market) {
-                            local_opps.push(opp);
-                        }
-                    }
-                }
-                local_opps
-            })
-            .collect();
-
-        opportunities.extend(cross_opps);
-        opportunities
-    }
-
-    fn calculate_similarity_sets(&self, words_a: &HashSet<String>, words_b: &HashSet<String>) -> f64 {
-        let intersection: HashSet<_> = words_a.intersection(words_b).collect();
-        let union_size = words_a.len() + words_b.len() - intersection.len();
-
-        if union_size == 0 { return 0.0; }
-        intersection.len() as f64 / union_size as f64
+    /// Cost every confident CrossMatcher match as YES on one market + NO on the other.
+    /// `IntraVenue` prices same-venue duplicates, `CrossPlatform` everything else.
+    pub(crate) fn price_cross_matches(&self, markets: &[Market], matches: &mut [CrossMatch], settings: &StrategySettings, kind: StrategyKind) {
+        let by_key: HashMap<String, &Market> = markets.iter().map(|m| (m.key(), m)).collect();
+        let min_confidence = settings.param("min_confidence", 0.5);
+        let same_venue = kind == StrategyKind::IntraVenue;
+
+        matches.par_iter_mut()
+            .filter(|cm| (cm.platform_a == cm.platform_b) == same_venue)
+            .filter(|cm| cm.confidence >= min_confidence)
+            .for_each(|cm| {
+                let pair = (
+                    by_key.get(&format!("{}:{}", cm.platform_a, cm.id_a)),
+                    by_key.get(&format!("{}:{}", cm.platform_b, cm.id_b)),
+                );
+                cm.execution = Some(match pair {
+                    (Some(a), Some(b)) => self.cost_cross_pair(a, b, settings, kind),
+                    _ => CrossExecution::NotExecutable { reason: "market not in this scan".to_string() },
+                });
+            });
     }
 
-
-
     /// COMBINATORIAL ARBITRAGE (From Roan's Article)
     /// Detects logical dependencies between markets and exploits price inconsistencies
     /// Key insight: If market A implies market B, then P(A) <= P(B)
     /// If P(A) > P(B), there's arbitrage: sell A, buy B
-    fn check_combinatorial_arbitrage(&self, markets: &[Market]) -> Vec<Opportunity> {
+    pub(crate) fn check_combinatorial_arbitrage(&self, markets: &[Market], dependencies: &[MarketDependency], settings: &StrategySettings) -> Vec<Opportunity> {
         let mut opportunities = Vec::new();
+        let min_gap = settings.param("min_gap", 0.02);
 
-        // Build dependency graph
-        let dependencies = self.detect_dependencies(markets);
-
-        for dep in &dependencies {
+        for dep in dependencies.iter().filter(|d| d.is_implication()) {
             let implying = &markets[dep.implying_market];
             let implied = &markets[dep.implied_market];
 
-            let implying_yes = implying.outcome_prices.get(0).copied().unwrap_or(0.0);
-            let implied_yes = implied.outcome_prices.get(0).copied().unwrap_or(0.0);
+            let implying_yes = implying.outcome_prices.first().copied().unwrap_or(0.0);
+            let implied_yes = implied.outcome_prices.first().copied().unwrap_or(0.0);
 
             // Skip unreliable prices
             if implying_yes < 0.01 || implied_yes < 0.01 {
@@ -313,7 +629,7 @@ impl ArbitrageEngine {
 
             // If A implies B, then P(A) must be <= P(B)
             // Violation: P(A) > P(B) creates arbitrage
-            if implying_yes > implied_yes + 0.02 { // 2% threshold
+            if implying_yes > implied_yes + min_gap {
                 let price_gap = implying_yes - implied_yes;
                 
                 // Arbitrage: Sell YES on implying (expensive), Buy YES on implied (cheap)
@@ -334,38 +650,51 @@ impl ArbitrageEngine {
                 let gross_profit = price_gap;
                 let net_profit = gross_profit - total_fees;
 
-                if net_profit >= self.min_profit_threshold {
+                if net_profit >= settings.min_profit {
                     let roi = (net_profit / total_cost) * 100.0;
-                    
+                    if roi < settings.min_roi * 100.0 {
+                        continue;
+                    }
+
                     let implying_text = self.get_market_text(implying);
                     let implied_text = self.get_market_text(implied);
 
                     opportunities.push(Opportunity {
                         id: format!("comb_{}_{}", implying.id, implied.id),
-                        opp_type: "Combinatorial".into(),
-                        description: format!(
-                            "LOGICAL: '{}' implies '{}' but priced higher",
-                            self.truncate_text(&implying_text, 25),
-                            self.truncate_text(&implied_text, 25)
-                        ),
-                        market_a: implying.id.clone(),
-                        market_b: implied.id.clone(),
-                        platform_a: implying.platform.clone(),
-                        platform_b: implied.platform.clone(),
-                        url_a: implying.url.clone().unwrap_or_default(),
-                        url_b: implied.url.clone().unwrap_or_default(),
-                        buy_yes_price: implied_yes,
-                        buy_no_price: implying_no,
+                        kind: StrategyKind::Combinatorial,
+                        description: match dep.dependency_type.as_str() {
+                            "conjunction" => format!(
+                                "PARLAY: '{}' priced above its leg '{}'",
+                                self.truncate_text(&implying_text, 25),
+                                self.truncate_text(&implied_text, 25)
+                            ),
+                            "disjunction" => format!(
+                                "EITHER-OR: '{}' priced below its clause '{}'",
+                                self.truncate_text(&implied_text, 25),
+                                self.truncate_text(&implying_text, 25)
+                            ),
+                            _ => format!(
+                                "LOGICAL: '{}' implies '{}' but priced higher",
+                                self.truncate_text(&implying_text, 25),
+                                self.truncate_text(&implied_text, 25)
+                            ),
+                        },
+                        legs: vec![
+                            self.leg(implying, Side::No, 1, implying_no, 1.0),
+                            self.leg(implied, Side::Yes, 0, implied_yes, 1.0),
+                        ],
                         total_cost,
                         gross_profit,
                         net_profit_after_fees: net_profit,
                         roi_percent: roi,
                         suggested_position: self.calculate_position_size(net_profit, total_cost),
-                        action: format!(
-                            "Buy NO on '{}' @${:.2} + Buy YES on '{}' @${:.2}",
-                            self.truncate_text(&implying_text, 15), implying_no,
-                            self.truncate_text(&implied_text, 15), implied_yes
-                        ),
+                        days_to_resolution: None,
+                        annualized_roi_percent: None,
+                        confidence: 1.0,
+                        resolution: None,
+                        sizing_limit: None,
+                        confirmation: None,
+                        timing: Timing::default(),
                     });
                 }
             }
@@ -383,27 +712,68 @@ impl ArbitrageEngine {
             .map(|m| self.get_market_text(m).to_lowercase())
             .collect();
 
-        // Build keyword index: pattern -> Vec<market_idx>
-        let mut keyword_idx_a: HashMap<usize, Vec<usize>> = HashMap::new();
-        let mut keyword_idx_b: HashMap<usize, Vec<usize>> = HashMap::new();
+        // Evaluate every rule condition once per market
+        let rule_matches: Vec<Vec<(bool, bool)>> = texts.par_iter()
+            .map(|text| self.rules.rules.iter()
+                .map(|rule| {
+                    if !rule.in_scope(text) {
+                        return (false, false);
+                    }
+                    (rule.a.matches(text), rule.b.matches(text))
+                })
+                .collect())
+            .collect();
 
-        for (i, text) in texts.iter().enumerate() {
-            for (p_idx, (pattern_a, pattern_b)) in IMPLICATION_PATTERNS.iter().enumerate() {
-                if text.contains(pattern_a) {
-                    keyword_idx_a.entry(p_idx).or_default().push(i);
-                }
-                if text.contains(pattern_b) {
-                    keyword_idx_b.entry(p_idx).or_default().push(i);
+        let push_rule_dependency = |dependencies: &mut Vec<MarketDependency>, relation: Relation, i: usize, j: usize| {
+            dependencies.push(MarketDependency {
+                implying_market: i,
+                implied_market: j,
+                dependency_type: relation.as_str().to_string(),
+            });
+            // Equivalence runs both ways
+            if relation == Relation::Equivalent {
+                dependencies.push(MarketDependency {
+                    implying_market: j,
+                    implied_market: i,
+                    dependency_type: relation.as_str().to_string(),
+                });
+            }
+        };
+
+        // Only check pairs that actually match rules (avoids O(n²) full scan)
+        for (r_idx, rule) in self.rules.rules.iter().enumerate() {
+            if rule.same_subject {
+                continue;
+            }
+            let a_markets: Vec<usize> = (0..texts.len()).filter(|&i| rule_matches[i][r_idx].0).collect();
+            let b_markets: Vec<usize> = (0..texts.len()).filter(|&i| rule_matches[i][r_idx].1).collect();
+            for &i in &a_markets {
+                for &j in &b_markets {
+                    if i != j && !(rule.b_excludes_a && rule_matches[j][r_idx].0) {
+                        push_rule_dependency(&mut dependencies, rule.relation, i, j);
+                    }
                 }
             }
         }
 
-        // Only check pairs that actually match patterns (avoids O(n²) full scan)
-        for (p_idx, _) in IMPLICATION_PATTERNS.iter().enumerate() {
-            if let (Some(a_markets), Some(b_markets)) = (keyword_idx_a.get(&p_idx), keyword_idx_b.get(&p_idx)) {
-                for &i in a_markets {
-                    for &j in b_markets {
-                        if i != j {
+        // Structured propositions: threshold and deadline implications for any values
+        // e.g. "BTC > 120k by June" => "BTC > 100k by June", "X by March" => "X by June"
+        let propositions: Vec<Option<Proposition>> = markets.par_iter()
+            .map(|m| self.parser.parse(&self.get_market_text(m), self.reference_date(m)))
+            .collect();
+
+        let mut proposition_groups: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, prop) in propositions.iter().enumerate() {
+            if let Some(p) = prop {
+                proposition_groups.entry(p.subject.as_str()).or_default().push(i);
+            }
+        }
+
+        for group in proposition_groups.values() {
+            for &i in group {
+                for &j in group {
+                    if let (Some(a), Some(b)) = (&propositions[i], &propositions[j]) {
+                        if i != j && a.implies(b) {
                             dependencies.push(MarketDependency {
                                 implying_market: i,
                                 implied_market: j,
@@ -415,145 +785,627 @@ impl ArbitrageEngine {
             }
         }
 
-        // Subset check (only for markets sharing subjects)
-        let subjects = [
-            // Politics
-            "trump", "biden", "harris", "republican", "democrat",
-            // Crypto
-            "bitcoin", "btc", "ethereum", "eth", "solana", "sol", "xrp", "doge",
-            // Sports
-            "lakers", "celtics", "warriors", "chiefs", "eagles", "yankees",
-            "lebron", "curry", "mahomes", "messi", "ronaldo",
-            // Tech/AI
-            "tesla", "nvidia", "apple", "google", "openai", "agi",
-            // Economics
-            "fed", "inflation", "recession", "gdp", "unemployment",
-        ];
+        // Mutually exclusive propositions: same contest with a different winner
+        // ("Trump wins 2028" vs "Vance wins 2028"), or flipped by an opposite word ("win" / "lose")
+        dependencies.extend(self.detect_exclusions(&propositions));
+
+        // Compound questions: "A and B" implies A and B, A or B is implied by either.
+        // Ahead of plain implications so the dedup below keeps the compound label.
+        let compounds = self.detect_compounds(markets, &propositions);
+        dependencies.splice(0..0, compounds);
 
-        // Group markets by subject
+        // Subject-scoped rules (only for markets sharing subjects)
         let mut subject_groups: HashMap<&str, Vec<usize>> = HashMap::new();
         for (i, text) in texts.iter().enumerate() {
-            for &subj in &subjects {
-                if text.contains(subj) {
-                    subject_groups.entry(subj).or_default().push(i);
+            for subj in &self.rules.subjects {
+                if text.contains(subj.as_str()) {
+                    subject_groups.entry(subj.as_str()).or_default().push(i);
                 }
             }
         }
 
-        // Only check subset within same subject group
-        for (_, group) in &subject_groups {
+        for group in subject_groups.values() {
             for &i in group {
                 for &j in group {
-                    if i != j && self.is_subset_market(&texts[i], &texts[j]) {
-                        dependencies.push(MarketDependency {
+                    if i == j {
+                        continue;
+                    }
+                    // First matching rule wins for a pair
+                    let rule = self.rules.rules.iter().enumerate()
+                        .filter(|(_, rule)| rule.same_subject)
+                        .find(|(r_idx, rule)| rule_matches[i][*r_idx].0 && rule_matches[j][*r_idx].1
+                            && !(rule.b_excludes_a && rule_matches[j][*r_idx].0));
+                    if let Some((_, rule)) = rule {
+                        push_rule_dependency(&mut dependencies, rule.relation, i, j);
+                    }
+                }
+            }
+        }
+
+        // Rules and propositions can flag the same pair
+        let mut seen: HashSet<(usize, usize, String)> = HashSet::new();
+        dependencies.retain(|d| {
+            let (a, b) = if d.dependency_type == "mutually_exclusive" {
+                // Symmetric relation: one direction is enough
+                (d.implying_market.min(d.implied_market), d.implying_market.max(d.implied_market))
+            } else {
+                (d.implying_market, d.implied_market)
+            };
+            let relation = match d.dependency_type.as_str() {
+                "conjunction" | "disjunction" => "implies",
+                other => other,
+            };
+            seen.insert((a, b, relation.to_string()))
+        });
+
+        dependencies
+    }
+
+    /// Link compound markets ("A and B", "either A or B", parlays) to markets
+    /// listing one of their clauses, on any venue
+    fn detect_compounds(&self, markets: &[Market], propositions: &[Option<Proposition>]) -> Vec<MarketDependency> {
+        // Parlay legs name the team ("yes Lakers"), the market asks "Will the Lakers win?"
+        let clause_key = |p: &Proposition| -> Proposition {
+            let subject: Vec<&str> = p.subject.split(' ')
+                .filter(|w| !self.rules.winner_terms.iter().any(|t| t == w))
+                .collect();
+            Proposition { subject: subject.join(" "), ..p.clone() }
+        };
+
+        let mut by_clause: HashMap<String, Vec<(usize, Proposition)>> = HashMap::new();
+        for (i, prop) in propositions.iter().enumerate() {
+            if let Some(p) = prop {
+                let key = clause_key(p);
+                by_clause.entry(key.subject.clone()).or_default().push((i, key));
+            }
+        }
+
+        let compounds: Vec<(usize, Compound)> = markets.par_iter().enumerate()
+            .filter_map(|(i, m)| {
+                let text = m.question.clone().or(m.title.clone())?;
+                self.parser.parse_compound(&text, self.reference_date(m)).map(|c| (i, c))
+            })
+            .collect();
+
+        let mut dependencies = Vec::new();
+        for (i, compound) in compounds {
+            for part in &compound.parts {
+                let key = clause_key(&part.proposition);
+                if key.subject.is_empty() {
+                    continue;
+                }
+                let components = by_clause.get(&key.subject).into_iter().flatten()
+                    .filter(|(j, prop)| *j != i && *prop == key);
+
+                for &(j, _) in components {
+                    let (implying, implied, dependency_type) = match (compound.connective, part.negated) {
+                        (Connective::And, false) => (i, j, "conjunction"),
+                        // A parlay needing "no B" can't win alongside B
+                        (Connective::And, true) => (i, j, "mutually_exclusive"),
+                        (Connective::Or, false) => (j, i, "disjunction"),
+                        // Either B resolves YES or "A or not B" does
+                        (Connective::Or, true) => (j, i, "exhaustive"),
+                    };
+                    dependencies.push(MarketDependency {
+                        implying_market: implying,
+                        implied_market: implied,
+                        dependency_type: dependency_type.to_string(),
+                    });
+                }
+            }
+        }
+        dependencies
+    }
+
+    fn detect_exclusions(&self, propositions: &[Option<Proposition>]) -> Vec<MarketDependency> {
+        let is_winner_term = |w: &str| self.rules.winner_terms.iter().any(|t| t == w);
+
+        // Block on "subject minus one word": two markets land in the same bucket
+        // exactly when their subjects differ by one word each
+        let mut buckets: HashMap<String, Vec<(usize, &str, bool)>> = HashMap::new();
+        for (i, prop) in propositions.iter().enumerate() {
+            let Some(p) = prop else { continue };
+            let words: Vec<&str> = p.subject.split(' ').collect();
+            if words.len() < 2 {
+                continue;
+            }
+
+            // The contestant comes before the contest ("trump win 2028 election"),
+            // except in "who will win X? - Trump" style titles
+            let first_winner_term = words.iter().position(|w| is_winner_term(w));
+            let is_who_question = words.contains(&"who");
+
+            for (w_idx, word) in words.iter().enumerate() {
+                let contestant_slot = is_who_question || first_winner_term.is_some_and(|t| w_idx < t);
+                let mut rest: Vec<&str> = words.iter().enumerate()
+                    .filter(|(k, _)| *k != w_idx)
+                    .map(|(_, w)| *w)
+                    .collect();
+                rest.sort_unstable();
+                buckets.entry(rest.join(" ")).or_default().push((i, word, contestant_slot));
+            }
+        }
+
+        let mut exclusions = Vec::new();
+        for (rest, members) in &buckets {
+            let is_contest = rest.split(' ').any(is_winner_term);
+
+            for (x, &(i, word_i, slot_i)) in members.iter().enumerate() {
+                for &(j, word_j, slot_j) in &members[x + 1..] {
+                    if i == j || word_i == word_j {
+                        continue;
+                    }
+                    let (Some(a), Some(b)) = (&propositions[i], &propositions[j]) else { continue };
+                    if a.comparator != b.comparator || a.threshold != b.threshold || a.deadline != b.deadline {
+                        continue;
+                    }
+
+                    let opposite = self.rules.opposites.iter()
+                        .any(|(p, q)| (p == word_i && q == word_j) || (p == word_j && q == word_i));
+                    // Years and counts name a different contest, not a rival
+                    let is_name = |w: &str| !w.chars().any(|c| c.is_ascii_digit()) && !is_winner_term(w);
+                    let rival = is_contest && slot_i && slot_j && is_name(word_i) && is_name(word_j);
+
+                    if opposite || rival {
+                        exclusions.push(MarketDependency {
                             implying_market: i,
                             implied_market: j,
-                            dependency_type: "subset".to_string(),
+                            dependency_type: Relation::MutuallyExclusive.as_str().to_string(),
                         });
                     }
                 }
             }
         }
 
-        dependencies
+        exclusions
     }
 
-    fn is_subset_market(&self, text_a: &str, text_b: &str) -> bool {
-        let subset_indicators = [
-            ("by 5+", "win"),
-            ("by 10+", "win"),
-            ("landslide", "win"),
-            ("sweep", "win"),
-            ("before march", "in 2025"),
-            ("by june", "in 2025"),
-            // Crypto price thresholds
-            ("200k", "100k"),
-            ("150k", "100k"),
-            ("10k", "5k"),
-            ("500", "300"),
-            // Sports
-            ("win in 4", "win series"),
-            ("win in 5", "win series"),
-            ("win finals", "reach finals"),
-            // Fed
-            ("cuts 3", "cut"),
-            ("cuts 4", "cuts 2"),
-        ];
+    /// Year context for deadlines written without one ("by March 31")
+    fn reference_date(&self, market: &Market) -> Option<NaiveDate> {
+        market.close_time().map(|dt| dt.date_naive())
+    }
+
+    /// EXCLUSION ARBITRAGE
+    /// At most one of two mutually exclusive markets resolves YES, so NO on both
+    /// pays at least $1. If P(A) + P(B) > 1, the two NOs cost less than that.
+    pub(crate) fn check_exclusion_arbitrage(&self, markets: &[Market], dependencies: &[MarketDependency], settings: &StrategySettings) -> Vec<Opportunity> {
+        let mut opportunities = Vec::new();
+
+        for dep in dependencies.iter().filter(|d| d.dependency_type == "mutually_exclusive") {
+            let market_a = &markets[dep.implying_market];
+            let market_b = &markets[dep.implied_market];
+
+            let yes_a = market_a.outcome_prices.first().copied().unwrap_or(0.0);
+            let no_a = market_a.outcome_prices.get(1).copied().unwrap_or(0.0);
+            let yes_b = market_b.outcome_prices.first().copied().unwrap_or(0.0);
+            let no_b = market_b.outcome_prices.get(1).copied().unwrap_or(0.0);
+
+            // Skip unreliable prices
+            if yes_a < 0.01 || no_a < 0.01 || yes_b < 0.01 || no_b < 0.01 {
+                continue;
+            }
+
+            if yes_a + yes_b <= 1.0 {
+                continue;
+            }
 
-        for (specific, general) in subset_indicators {
-            if text_a.contains(specific) && text_b.contains(general) 
-                && !text_b.contains(specific) {
-                return true;
+            let total_cost = no_a + no_b;
+            let fee_a = self.get_platform_fee(&market_a.platform);
+            let fee_b = self.get_platform_fee(&market_b.platform);
+            let total_fees = (no_a * fee_a) + (no_b * fee_b);
+            let gross_profit = 1.0 - total_cost;
+            let net_profit = gross_profit - total_fees;
+
+            if net_profit >= settings.min_profit {
+                let roi = (net_profit / total_cost) * 100.0;
+                if roi < settings.min_roi * 100.0 {
+                    continue;
+                }
+
+                let text_a = self.get_market_text(market_a);
+                let text_b = self.get_market_text(market_b);
+
+                opportunities.push(Opportunity {
+                    id: format!("excl_{}_{}", market_a.id, market_b.id),
+                    kind: StrategyKind::Exclusion,
+                    description: format!(
+                        "EXCLUSIVE: '{}' and '{}' priced {:.0}% combined",
+                        self.truncate_text(&text_a, 25),
+                        self.truncate_text(&text_b, 25),
+                        (yes_a + yes_b) * 100.0
+                    ),
+                    legs: vec![
+                        self.leg(market_a, Side::No, 1, no_a, 1.0),
+                        self.leg(market_b, Side::No, 1, no_b, 1.0),
+                    ],
+                    total_cost,
+                    gross_profit,
+                    net_profit_after_fees: net_profit,
+                    roi_percent: roi,
+                    suggested_position: self.calculate_position_size(net_profit, total_cost),
+                    days_to_resolution: None,
+                    annualized_roi_percent: None,
+                    confidence: 1.0,
+                    resolution: None,
+                    sizing_limit: None,
+                    confirmation: None,
+                    timing: Timing::default(),
+                });
             }
         }
 
-        false
+        opportunities
     }
 
     /// Multi-condition market rebalancing
-    /// If a market has multiple outcomes (A, B, C, D) that sum != 1, there's arbitrage
-    fn check_multi_condition_rebalancing(&self, markets: &[Market]) -> Vec<Opportunity> {
+    /// If a market has multiple outcomes (A, B, C, D) that sum != 1, there's arbitrage:
+    /// - Sum < 1: buy YES on every outcome, exactly one pays $1
+    /// - Sum > 1: buy NO on every outcome, N-1 of them pay $1
+    pub(crate) fn check_multi_condition_rebalancing(&self, markets: &[Market], settings: &StrategySettings) -> Vec<Opportunity> {
         let mut opportunities = Vec::new();
 
         for market in markets {
             // Check markets with more than 2 outcomes
-            if market.outcome_prices.len() > 2 {
-                let total: f64 = market.outcome_prices.iter().sum();
-                
-                // If sum of all outcomes < 1, buy all (guaranteed $1 payout)
-                if total < 1.0 && total > 0.0 {
-                    let gross_profit = 1.0 - total;
-                    let fee = self.get_platform_fee(&market.platform);
-                    let total_fees = total * fee;
-                    let net_profit = gross_profit - total_fees;
-
-                    if net_profit >= self.min_profit_threshold {
-                        let roi = (net_profit / total) * 100.0;
-
-                        opportunities.push(Opportunity {
-                            id: format!("multi_{}", market.id),
-                            opp_type: "Multi-Condition".into(),
-                            description: format!(
-                                "{} outcomes sum to ${:.2} (should be $1.00)",
-                                market.outcome_prices.len(),
-                                total
-                            ),
-                            market_a: market.id.clone(),
-                            market_b: market.id.clone(),
-                            platform_a: market.platform.clone(),
-                            platform_b: market.platform.clone(),
-                            url_a: market.url.clone().unwrap_or_default(),
-                            url_b: market.url.clone().unwrap_or_default(),
-                            buy_yes_price: total,
-                            buy_no_price: 0.0,
-                            total_cost: total,
-                            gross_profit,
-                            net_profit_after_fees: net_profit,
-                            roi_percent: roi,
-                            suggested_position: self.calculate_position_size(net_profit, total),
-                            action: format!(
-                                "Buy ALL {} outcomes on {} for ${:.2}",
-                                market.outcome_prices.len(),
-                                market.platform,
-                                total
-                            ),
-                        });
+            let n = market.outcome_prices.len();
+            if n <= 2 {
+                continue;
+            }
+
+            let total: f64 = market.outcome_prices.iter().sum();
+            if total <= 0.0 || total == 1.0 {
+                continue;
+            }
+
+            let buy_yes = total < 1.0;
+
+            // NO on outcome i is priced as its complement (venue-native conversion)
+            let legs: Vec<Leg> = market.outcome_prices.iter().enumerate()
+                .map(|(i, &p)| {
+                    if buy_yes {
+                        self.leg(market, Side::Yes, i, p, 1.0)
+                    } else {
+                        self.leg(market, Side::No, i, 1.0 - p, 1.0)
                     }
-                }
+                })
+                .collect();
+
+            let total_cost: f64 = legs.iter().map(|l| l.cost()).sum();
+            let total_fees: f64 = legs.iter().map(|l| l.fee).sum();
+            let payout = if buy_yes { 1.0 } else { (n - 1) as f64 };
+            let gross_profit = payout - total_cost;
+            let net_profit = gross_profit - total_fees;
+
+            if net_profit < settings.min_profit || total_cost <= 0.0 {
+                continue;
+            }
+
+            let roi = (net_profit / total_cost) * 100.0;
+            if roi < settings.min_roi * 100.0 {
+                continue;
             }
+
+            let (id, side_label) = if buy_yes {
+                (format!("multi_{}", market.id), "YES")
+            } else {
+                (format!("multi_no_{}", market.id), "NO")
+            };
+
+            opportunities.push(Opportunity {
+                id,
+                kind: StrategyKind::MultiCondition,
+                description: format!(
+                    "{} outcomes sum to ${:.2} (should be $1.00): buy {} on all, {} pay out $1",
+                    n,
+                    total,
+                    side_label,
+                    payout
+                ),
+                legs,
+                total_cost,
+                gross_profit,
+                net_profit_after_fees: net_profit,
+                roi_percent: roi,
+                suggested_position: self.calculate_position_size(net_profit, total_cost),
+                days_to_resolution: None,
+                annualized_roi_percent: None,
+                confidence: 1.0,
+                resolution: None,
+                sizing_limit: None,
+                confirmation: None,
+                timing: Timing::default(),
+            });
         }
 
         opportunities
     }
 
-    fn calculate_cross_platform_spread(&self, market_a: &Market, market_b: &Market) -> Option<Opportunity> {
-        let yes_a = market_a.outcome_prices.get(0).copied().unwrap_or(0.0);
+    /// LP BASKET ARBITRAGE
+    /// Groups markets connected in the dependency graph and solves for the cheapest
+    /// YES/NO portfolio that pays out in every logically possible world.
+    /// Pairs are left to check_combinatorial_arbitrage; this covers 3+ market sets.
+    pub(crate) fn check_lp_arbitrage(&self, markets: &[Market], dependencies: &[MarketDependency], settings: &StrategySettings) -> Vec<Opportunity> {
+        let reliable = |i: usize| {
+            let prices = &markets[i].outcome_prices;
+            prices.len() >= 2 && prices[0] >= 0.01 && prices[1] >= 0.01
+        };
+
+        // Union-find over dependency edges
+        fn find(parent: &mut [usize], i: usize) -> usize {
+            let mut root = i;
+            while parent[root] != root {
+                root = parent[root];
+            }
+            parent[i] = root;
+            root
+        }
+
+        let usable: Vec<&MarketDependency> = dependencies.iter()
+            .filter(|d| d.implying_market != d.implied_market)
+            .filter(|d| reliable(d.implying_market) && reliable(d.implied_market))
+            .collect();
+
+        let mut parent: Vec<usize> = (0..markets.len()).collect();
+        for dep in &usable {
+            let a = find(&mut parent, dep.implying_market);
+            let b = find(&mut parent, dep.implied_market);
+            if a != b {
+                parent[a] = b;
+            }
+        }
+
+        let mut components: HashMap<usize, (Vec<usize>, Vec<&MarketDependency>)> = HashMap::new();
+        for dep in &usable {
+            let root = find(&mut parent, dep.implying_market);
+            let entry = components.entry(root).or_default();
+            for idx in [dep.implying_market, dep.implied_market] {
+                if !entry.0.contains(&idx) {
+                    entry.0.push(idx);
+                }
+            }
+            entry.1.push(dep);
+        }
+
+        // Too many markets to enumerate worlds: solve connected chunks instead. Dropping the
+        // constraints to markets outside a chunk only adds worlds, so chunk baskets stay sound.
+        let components: Vec<(Vec<usize>, Vec<&MarketDependency>)> = components.into_values()
+            .flat_map(|(members, deps)| {
+                if members.len() <= MAX_LP_MARKETS {
+                    vec![(members, deps)]
+                } else {
+                    split_component(&members, &deps)
+                }
+            })
+            .filter(|(members, _)| members.len() >= 3)
+            .collect();
+
+        components.par_iter()
+            .filter_map(|(members, deps)| self.solve_lp_basket(markets, members, deps, settings))
+            .collect()
+    }
+
+    fn solve_lp_basket(&self, markets: &[Market], members: &[usize], deps: &[&MarketDependency], settings: &StrategySettings) -> Option<Opportunity> {
+        let mut members = members.to_vec();
+        members.sort_by(|a, b| markets[*a].id.cmp(&markets[*b].id));
+        let local: HashMap<usize, usize> = members.iter().enumerate()
+            .map(|(local_idx, &global_idx)| (global_idx, local_idx))
+            .collect();
+
+        let constraints: Vec<LogicalConstraint> = deps.iter()
+            .filter_map(|d| d.to_constraint(local[&d.implying_market], local[&d.implied_market]))
+            .collect();
+
+        let lp_markets: Vec<LpMarket> = members.iter()
+            .map(|&i| {
+                let m = &markets[i];
+                let fee = self.get_platform_fee(&m.platform);
+                LpMarket {
+                    yes_cost: m.outcome_prices[0] * (1.0 + fee),
+                    no_cost: m.outcome_prices[1] * (1.0 + fee),
+                }
+            })
+            .collect();
+
+        let solution = lp_solver::solve(&lp_markets, &constraints)?;
+        let first = &markets[members[0]];
+        let ids: Vec<&str> = members.iter().map(|&i| markets[i].id.as_str()).collect();
+        let description = format!(
+            "{}-market logical basket ({} feasible worlds): '{}' ...",
+            members.len(),
+            solution.feasible_worlds,
+            self.truncate_text(&self.get_market_text(first), 25)
+        );
+
+        self.basket_opportunity(markets, &members, &solution, settings, StrategyKind::LpBasket, format!("lp_{}", ids.join("_")), description, 1.0)
+    }
+
+    /// THRESHOLD LADDERS
+    /// P(above X) can't rise with X, and brackets must sum to 1 and agree with the
+    /// thresholds. Each region between breakpoints is one possible world, so the
+    /// cheapest basket paying $1 in all of them is a guaranteed profit when it costs less.
+    pub(crate) fn check_ladder_arbitrage(&self, markets: &[Market], settings: &StrategySettings) -> Vec<Opportunity> {
+        let ladders = ladder::assemble(&self.parser, markets, |m| self.reference_date(m));
+
+        ladders.par_iter()
+            .filter_map(|l| self.solve_ladder(markets, l, settings))
+            .collect()
+    }
+
+    fn solve_ladder(&self, markets: &[Market], ladder: &Ladder, settings: &StrategySettings) -> Option<Opportunity> {
+        let worlds = ladder.worlds();
+        if worlds.len() < 2 {
+            return None;
+        }
+
+        let members: Vec<usize> = ladder.rungs.iter().map(|r| r.market).collect();
+        let lp_markets: Vec<LpMarket> = members.iter()
+            .map(|&i| {
+                let m = &markets[i];
+                let fee = self.get_platform_fee(&m.platform);
+                LpMarket {
+                    yes_cost: m.outcome_prices[0] * (1.0 + fee),
+                    no_cost: m.outcome_prices[1] * (1.0 + fee),
+                }
+            })
+            .collect();
+
+        let solution = lp_solver::solve_worlds(&lp_markets, &worlds)?;
+        if solution.total_cost >= solution.worst_case_payout {
+            return None;
+        }
+
+        let risk = self.basket_resolution_risk(markets, &members, &solution, |diff| diff.risk);
+
+        let mut venues: Vec<&str> = members.iter().map(|&i| markets[i].platform.as_str()).collect();
+        venues.sort();
+        venues.dedup();
+        let ids: Vec<&str> = members.iter().map(|&i| markets[i].id.as_str()).collect();
+        let description = format!(
+            "{}-rung ladder '{}' ({}, {}): {}",
+            members.len(),
+            self.truncate_text(&ladder.subject, 30),
+            ladder.deadline,
+            venues.join("/"),
+            ladder.violation(markets)
+        );
+
+        self.basket_opportunity(markets, &members, &solution, settings, StrategyKind::Ladder, format!("ladder_{}", ids.join("_")), description, 1.0 - risk)
+    }
+
+    /// CALENDAR SPREADS
+    /// "X by March 31" can't be priced above "X by June 30": each window the event
+    /// can happen in (or never) is one world, so the LP finds inverted term structures.
+    pub(crate) fn check_calendar_arbitrage(&self, markets: &[Market], settings: &StrategySettings) -> Vec<Opportunity> {
+        let families = calendar::assemble(&self.parser, markets, |m| self.reference_date(m));
+
+        families.par_iter()
+            .filter_map(|f| self.solve_calendar(markets, f, settings))
+            .collect()
+    }
+
+    fn solve_calendar(&self, markets: &[Market], family: &TermStructure, settings: &StrategySettings) -> Option<Opportunity> {
+        let members: Vec<usize> = family.points.iter().map(|p| p.market).collect();
+        let lp_markets: Vec<LpMarket> = members.iter()
+            .map(|&i| {
+                let m = &markets[i];
+                let fee = self.get_platform_fee(&m.platform);
+                LpMarket {
+                    yes_cost: m.outcome_prices[0] * (1.0 + fee),
+                    no_cost: m.outcome_prices[1] * (1.0 + fee),
+                }
+            })
+            .collect();
+
+        let solution = lp_solver::solve_worlds(&lp_markets, &family.worlds())?;
+        if solution.total_cost >= solution.worst_case_payout {
+            return None;
+        }
+
+        // Cutoffs differ by design here, so only the rules text counts against confidence
+        let risk = self.basket_resolution_risk(markets, &members, &solution, |diff| diff.rules_risk());
+
+        let ids: Vec<&str> = members.iter().map(|&i| markets[i].id.as_str()).collect();
+        let description = format!(
+            "CALENDAR: '{}' across {} deadlines: {}",
+            self.truncate_text(&family.subject, 30),
+            family.worlds().len() - 1,
+            family.describe(markets)
+        );
+
+        self.basket_opportunity(markets, &members, &solution, settings, StrategyKind::Calendar, format!("cal_{}", ids.join("_")), description, 1.0 - risk)
+    }
+
+    /// Worst resolution risk between any two venues the solution trades on
+    fn basket_resolution_risk(&self, markets: &[Market], members: &[usize], solution: &LpSolution, risk: impl Fn(&ResolutionDiff) -> f64) -> f64 {
+        let mut worst: f64 = 0.0;
+        for (n, a) in solution.positions.iter().enumerate() {
+            for b in &solution.positions[n + 1..] {
+                let (ma, mb) = (&markets[members[a.market]], &markets[members[b.market]]);
+                if ma.platform != mb.platform {
+                    worst = worst.max(risk(&self.resolution.compare(ma, mb)));
+                }
+            }
+        }
+        worst
+    }
+
+    /// Turn an LP solution over `members` into an opportunity, if it clears the thresholds
+    #[allow(clippy::too_many_arguments)]
+    fn basket_opportunity(
+        &self,
+        markets: &[Market],
+        members: &[usize],
+        solution: &LpSolution,
+        settings: &StrategySettings,
+        kind: StrategyKind,
+        id: String,
+        description: String,
+        confidence: f64,
+    ) -> Option<Opportunity> {
+        let payout = solution.worst_case_payout;
+        let net_profit = payout - solution.total_cost;
+        if solution.positions.is_empty() || net_profit < settings.min_profit {
+            return None;
+        }
+
+        let legs: Vec<Leg> = solution.positions.iter()
+            .map(|pos| {
+                let m = &markets[members[pos.market]];
+                match pos.side {
+                    Side::Yes => self.leg(m, Side::Yes, 0, m.outcome_prices[0], pos.shares),
+                    Side::No => self.leg(m, Side::No, 1, m.outcome_prices[1], pos.shares),
+                }
+            })
+            .collect();
+
+        let total_cost: f64 = legs.iter().map(|l| l.cost()).sum();
+        let roi = (net_profit / solution.total_cost) * 100.0;
+        if total_cost <= 0.0 || roi < settings.min_roi * 100.0 {
+            return None;
+        }
+
+        Some(Opportunity {
+            id,
+            kind,
+            description,
+            legs,
+            total_cost,
+            gross_profit: payout - total_cost,
+            net_profit_after_fees: net_profit,
+            roi_percent: roi,
+            suggested_position: self.calculate_position_size(net_profit, solution.total_cost),
+            days_to_resolution: None,
+            annualized_roi_percent: None,
+            confidence,
+            resolution: None,
+            sizing_limit: None,
+            confirmation: None,
+            timing: Timing::default(),
+        })
+    }
+
+    fn cost_cross_pair(&self, market_a: &Market, market_b: &Market, settings: &StrategySettings, kind: StrategyKind) -> CrossExecution {
+        let not_executable = |reason: String| CrossExecution::NotExecutable { reason };
+
+        for m in [market_a, market_b] {
+            if !m.currency.is_tradable() {
+                return not_executable(format!("{} market is play money", m.platform));
+            }
+        }
+
+        let yes_a = market_a.outcome_prices.first().copied().unwrap_or(0.0);
         let no_a = market_a.outcome_prices.get(1).copied().unwrap_or(0.0);
-        let yes_b = market_b.outcome_prices.get(0).copied().unwrap_or(0.0);
+        let yes_b = market_b.outcome_prices.first().copied().unwrap_or(0.0);
         let no_b = market_b.outcome_prices.get(1).copied().unwrap_or(0.0);
 
         if yes_a == 0.0 || no_a == 0.0 || yes_b == 0.0 || no_b == 0.0 {
-            return None;
+            return not_executable("missing prices".to_string());
+        }
+
+        // Same question text is not the same contract: skip pairs whose rules diverge too much
+        let resolution = self.resolution.compare(market_a, market_b);
+        let max_risk = settings.param("max_resolution_risk", 0.75);
+        if resolution.risk > max_risk {
+            return not_executable(format!("resolution risk {:.0}% > {:.0}%", resolution.risk * 100.0, max_risk * 100.0));
         }
 
         // Strategy 1: Buy YES on A + Buy NO on B
@@ -568,88 +1420,97 @@ impl ArbitrageEngine {
         let fees_2 = (yes_b * fee_b) + (no_a * fee_a);
         let net_profit_2 = 1.0 - cost_2 - fees_2;
 
-        let (best_cost, best_net_profit, buy_yes_market, buy_no_market, buy_yes_price, buy_no_price) = 
+        let (best_cost, best_fees, best_net_profit, buy_yes_market, buy_no_market, buy_yes_price, buy_no_price) =
             if net_profit_1 > net_profit_2 {
-                (cost_1, net_profit_1, market_a, market_b, yes_a, no_b)
+                (cost_1, fees_1, net_profit_1, market_a, market_b, yes_a, no_b)
             } else {
-                (cost_2, net_profit_2, market_b, market_a, yes_b, no_a)
+                (cost_2, fees_2, net_profit_2, market_b, market_a, yes_b, no_a)
             };
 
-        if best_net_profit >= self.min_profit_threshold && best_cost > 0.0 {
-            let roi = (best_net_profit / best_cost) * 100.0;
-            
-            if roi >= self.min_roi * 100.0 {
-                let gross_profit = 1.0 - best_cost;
-                let position_size = self.calculate_position_size(best_net_profit, best_cost);
-                
-                let description = format!(
-                    "{}",
-                    buy_yes_market.question.clone()
-                        .or(buy_yes_market.title.clone())
-                        .unwrap_or_default()
-                );
-
-                return Some(Opportunity {
-                    id: format!("cross_{}_{}", buy_yes_market.id, buy_no_market.id),
-                    opp_type: "Cross-Platform".into(),
-                    description: self.truncate_text(&description, 50),
-                    market_a: buy_yes_market.id.clone(),
-                    market_b: buy_no_market.id.clone(),
-                    platform_a: buy_yes_market.platform.clone(),
-                    platform_b: buy_no_market.platform.clone(),
-                    url_a: buy_yes_market.url.clone().unwrap_or_default(),
-                    url_b: buy_no_market.url.clone().unwrap_or_default(),
-                    buy_yes_price,
-                    buy_no_price,
-                    total_cost: best_cost,
-                    gross_profit,
-                    net_profit_after_fees: best_net_profit,
-                    roi_percent: roi,
-                    suggested_position: position_size,
-                    action: format!(
-                        "Buy YES @${:.2} on {} + Buy NO @${:.2} on {}",
-                        buy_yes_price, buy_yes_market.platform,
-                        buy_no_price, buy_no_market.platform
-                    ),
-                });
-            }
+        let roi = if best_cost > 0.0 { (best_net_profit / best_cost) * 100.0 } else { 0.0 };
+        if best_net_profit < settings.min_profit || best_cost <= 0.0 || roi < settings.min_roi * 100.0 {
+            return CrossExecution::NoArbAfterFees {
+                total_cost: best_cost,
+                fees: best_fees,
+                net_profit: best_net_profit,
+            };
         }
 
-        None
+        let gross_profit = 1.0 - best_cost;
+        let position_size = self.calculate_position_size(best_net_profit, best_cost);
+
+        let description = buy_yes_market.question.clone()
+            .or(buy_yes_market.title.clone())
+            .unwrap_or_default();
+
+        let prefix = if kind == StrategyKind::IntraVenue { "dup" } else { "cross" };
+
+        CrossExecution::Arb(Box::new(Opportunity {
+            id: format!("{}_{}_{}", prefix, buy_yes_market.id, buy_no_market.id),
+            kind,
+            description: self.truncate_text(&description, 50),
+            legs: vec![
+                self.leg(buy_yes_market, Side::Yes, 0, buy_yes_price, 1.0),
+                self.leg(buy_no_market, Side::No, 1, buy_no_price, 1.0),
+            ],
+            total_cost: best_cost,
+            gross_profit,
+            net_profit_after_fees: best_net_profit,
+            roi_percent: roi,
+            suggested_position: position_size,
+            days_to_resolution: None,
+            annualized_roi_percent: None,
+            confidence: 1.0 - resolution.risk,
+            resolution: Some(resolution),
+            sizing_limit: None,
+            confirmation: None,
+            timing: Timing::default(),
+        }))
     }
 
-    fn get_market_text(&self, market: &Market) -> String {
-        format!(
-            "{} {} {}",
-            market.question.clone().unwrap_or_default(),
-            market.title.clone().unwrap_or_default(),
-            market.subtitle.clone().unwrap_or_default()
-        )
-    }
-
-    fn calculate_similarity(&self, text_a: &str, text_b: &str) -> f64 {
-        let stop_words = ["the", "a", "an", "is", "will", "be", "to", "of", "in", "for", "on", "at", "by"];
-        
-        let filter_words = |text: &str| -> HashSet<String> {
-            text.split_whitespace()
-                .map(|w| w.to_lowercase())
-                .filter(|w| !stop_words.contains(&w.as_str()) && w.len() > 2)
-                .collect()
+    /// Annualize ROI over the time to resolution and subtract the cost of capital
+    fn apply_time_value(&self, opp: &mut Opportunity, now: DateTime<Utc>) {
+        let Some(resolution) = opp.resolution_time() else {
+            opp.annualized_roi_percent = Some(opp.roi_percent * 365.0 / UNDATED_HORIZON_DAYS - self.cost_of_capital * 100.0);
+            return;
         };
 
-        let words_a = filter_words(text_a);
-        let words_b = filter_words(text_b);
-
-        if words_a.is_empty() || words_b.is_empty() {
-            return 0.0;
-        }
+        // Floor at one day so same-day resolutions don't annualize to absurd numbers
+        let days = ((resolution - now).num_seconds() as f64 / 86_400.0).max(MIN_DAYS_TO_RESOLUTION);
+        opp.days_to_resolution = Some(days);
+        opp.annualized_roi_percent = Some(opp.roi_percent * 365.0 / days - self.cost_of_capital * 100.0);
+    }
 
-        let intersection: HashSet<_> = words_a.intersection(&words_b).collect();
-        let union_size = words_a.len() + words_b.len() - intersection.len();
+    fn get_market_text(&self, market: &Market) -> String {
+        market.full_text()
+    }
 
-        if union_size == 0 { return 0.0; }
+    /// Leg buying `side` of outcome `outcome_idx`, with the venue fee on its cost
+    fn leg(&self, market: &Market, side: Side, outcome_idx: usize, price: f64, size: f64) -> Leg {
+        let outcome = market.outcomes.get(outcome_idx).cloned().unwrap_or_else(|| {
+            match (market.outcome_prices.len(), outcome_idx) {
+                (2, 0) => "Yes".to_string(),
+                (2, _) => "No".to_string(),
+                _ => format!("Outcome {}", outcome_idx + 1),
+            }
+        });
 
-        intersection.len() as f64 / union_size as f64
+        Leg {
+            market_key: market.key(),
+            event_key: market.event_key(),
+            platform: market.platform.clone(),
+            question: market.question.clone().or(market.title.clone()).unwrap_or_default(),
+            url: market.url.clone().unwrap_or_default(),
+            side,
+            outcome,
+            outcome_index: outcome_idx,
+            limit_price: price,
+            size,
+            fee: price * size * self.get_platform_fee(&market.platform),
+            close_time: market.close_time(),
+            fetched_at: market.fetched_at,
+            venue_time: market.venue_time,
+        }
     }
 
     fn calculate_position_size(&self, net_profit: f64, cost: f64) -> f64 {
@@ -669,11 +1530,249 @@ impl ArbitrageEngine {
         }
     }
 
+    /// First `max_len` characters (not bytes, so non-ASCII text can't split mid-char)
     fn truncate_text(&self, text: &str, max_len: usize) -> String {
-        if text.len() > max_len {
-            format!("{}...", &text[..max_len])
+        if text.chars().count() > max_len {
+            format!("{}...", text.chars().take(max_len).collect::<String>())
         } else {
             text.to_string()
         }
     }
 }
+
+/// Split an oversized dependency component into near-equal chunks of at most
+/// `MAX_LP_MARKETS`, in breadth-first order so each chunk keeps its neighbours,
+/// each with the dependencies between its own markets
+fn split_component<'a>(members: &[usize], deps: &[&'a MarketDependency]) -> Vec<(Vec<usize>, Vec<&'a MarketDependency>)> {
+    let mut neighbours: HashMap<usize, Vec<usize>> = HashMap::new();
+    for dep in deps {
+        neighbours.entry(dep.implying_market).or_default().push(dep.implied_market);
+        neighbours.entry(dep.implied_market).or_default().push(dep.implying_market);
+    }
+    for list in neighbours.values_mut() {
+        list.sort_unstable();
+        list.dedup();
+    }
+
+    let mut order = Vec::with_capacity(members.len());
+    let mut seen: HashSet<usize> = HashSet::new();
+    let mut starts = members.to_vec();
+    starts.sort_unstable();
+    for start in starts {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut queue = std::collections::VecDeque::from([start]);
+        while let Some(i) = queue.pop_front() {
+            order.push(i);
+            for &j in neighbours.get(&i).map(Vec::as_slice).unwrap_or_default() {
+                if seen.insert(j) {
+                    queue.push_back(j);
+                }
+            }
+        }
+    }
+
+    let chunk_size = order.len().div_ceil(order.len().div_ceil(MAX_LP_MARKETS));
+    order.chunks(chunk_size)
+        .map(|chunk| {
+            let inside: HashSet<usize> = chunk.iter().copied().collect();
+            let chunk_deps = deps.iter()
+                .filter(|d| inside.contains(&d.implying_market) && inside.contains(&d.implied_market))
+                .copied()
+                .collect();
+            (chunk.to_vec(), chunk_deps)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Engine with config defaults and the built-in rules
+    fn engine() -> ArbitrageEngine {
+        let config: Config = serde_json::from_str("{}").unwrap();
+        ArbitrageEngine::new(&config, 10_000.0, RuleSet::from_json(crate::rules::DEFAULT_RULES).unwrap())
+    }
+
+    #[test]
+    fn oversized_component_splits_into_connected_chunks() {
+        // A 30-market implies chain
+        let deps: Vec<MarketDependency> = (0..29)
+            .map(|i| MarketDependency { implying_market: i, implied_market: i + 1, dependency_type: "implies".to_string() })
+            .collect();
+        let dep_refs: Vec<&MarketDependency> = deps.iter().collect();
+        let members: Vec<usize> = (0..30).collect();
+
+        let chunks = split_component(&members, &dep_refs);
+        assert_eq!(chunks.len(), 3);
+        for (chunk, chunk_deps) in &chunks {
+            assert_eq!(chunk.len(), 10);
+            assert_eq!(chunk_deps.len(), 9);
+        }
+        let mut covered: Vec<usize> = chunks.iter().flat_map(|(chunk, _)| chunk.clone()).collect();
+        covered.sort_unstable();
+        assert_eq!(covered, members);
+    }
+
+    fn settings(kind: StrategyKind) -> StrategySettings {
+        let config: Config = serde_json::from_str("{}").unwrap();
+        StrategySettings::from_config(&config, kind)
+    }
+
+    fn exclusive_pairs(deps: &[MarketDependency]) -> Vec<(usize, usize)> {
+        let mut pairs: Vec<(usize, usize)> = deps.iter()
+            .filter(|d| d.dependency_type == "mutually_exclusive")
+            .map(|d| (d.implying_market.min(d.implied_market), d.implying_market.max(d.implied_market)))
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    #[test]
+    fn rival_winners_and_opposites_are_exclusive() {
+        let markets = [
+            Market::test("polymarket", "a", "Will Trump win the 2028 election?", &[0.55, 0.45]),
+            Market::test("kalshi", "b", "Will Vance win the 2028 election?", &[0.50, 0.50]),
+            Market::test("polymarket", "c", "Will Trump win the 2032 election?", &[0.20, 0.80]),
+            Market::test("polymarket", "d", "Will the Lakers win the game?", &[0.60, 0.40]),
+            Market::test("kalshi", "e", "Will the Lakers lose the game?", &[0.45, 0.55]),
+        ];
+        let deps = engine().detect_dependencies(&markets);
+        // Different years are different contests, not rivals
+        assert_eq!(exclusive_pairs(&deps), vec![(0, 1), (3, 4)]);
+    }
+
+    #[test]
+    fn overpriced_exclusive_pair_buys_both_nos() {
+        let markets = [
+            Market::test("polymarket", "a", "Will Trump win the 2028 election?", &[0.60, 0.40]),
+            Market::test("kalshi", "b", "Will Vance win the 2028 election?", &[0.50, 0.50]),
+        ];
+        let deps = [MarketDependency { implying_market: 0, implied_market: 1, dependency_type: "mutually_exclusive".to_string() }];
+        let opps = engine().check_exclusion_arbitrage(&markets, &deps, &settings(StrategyKind::Exclusion));
+        assert_eq!(opps.len(), 1);
+
+        let opp = &opps[0];
+        assert!(opp.legs.iter().all(|l| l.side == Side::No));
+        assert!((opp.total_cost - 0.9).abs() < 1e-9);
+        // 2% on the Polymarket NO, 1% on the Kalshi NO
+        assert!((opp.net_profit_after_fees - (0.1 - 0.4 * 0.02 - 0.5 * 0.01)).abs() < 1e-9);
+
+        // Fairly priced: YES prices sum to 1, nothing to buy
+        let fair = [
+            Market::test("polymarket", "a", "Will Trump win the 2028 election?", &[0.50, 0.50]),
+            Market::test("kalshi", "b", "Will Vance win the 2028 election?", &[0.50, 0.50]),
+        ];
+        assert!(engine().check_exclusion_arbitrage(&fair, &deps, &settings(StrategyKind::Exclusion)).is_empty());
+    }
+
+    #[test]
+    fn overpriced_outcomes_buy_a_no_basket_net_of_per_leg_fees() {
+        let market = Market::test("kalshi", "m", "Who wins the 2028 election?", &[0.35, 0.30, 0.25, 0.20]);
+        let opps = engine().check_multi_condition_rebalancing(std::slice::from_ref(&market), &settings(StrategyKind::MultiCondition));
+        assert_eq!(opps.len(), 1);
+
+        let opp = &opps[0];
+        assert_eq!(opp.id, "multi_no_m");
+        assert_eq!(opp.legs.len(), 4);
+        assert!(opp.legs.iter().all(|l| l.side == Side::No));
+        // NO is the complement of each outcome, and three of the four NOs pay out
+        assert!((opp.legs[0].limit_price - 0.65).abs() < 1e-9);
+        assert!((opp.legs[0].price_in(&market).unwrap() - 0.65).abs() < 1e-9);
+        assert!((opp.total_cost - 2.9).abs() < 1e-9);
+        assert!((opp.gross_profit - 0.1).abs() < 1e-9);
+        // 1% Kalshi fee on each leg's cost
+        assert!((opp.total_fees() - 0.029).abs() < 1e-9);
+        assert!((opp.net_profit_after_fees - 0.071).abs() < 1e-9);
+
+        // A 2% overround on Polymarket is eaten by the 2% fee on ~$2.98 of NOs
+        let thin = Market::test("polymarket", "t", "Who wins the 2028 election?", &[0.32, 0.30, 0.25, 0.15]);
+        assert!(engine().check_multi_condition_rebalancing(&[thin], &settings(StrategyKind::MultiCondition)).is_empty());
+    }
+
+    #[test]
+    fn non_monotone_ladder_is_an_arb() {
+        // P(above 100k) priced over P(above 90k): YES 90k + NO 100k pays at least $1
+        let markets: Vec<Market> = [("a", "90k", 0.40), ("b", "100k", 0.50)].iter()
+            .map(|&(id, level, p)| Market {
+                close_date: Some("2025-06-30".to_string()),
+                ..Market::test("kalshi", id, &format!("Will BTC be above {} on June 30?", level), &[p, 1.0 - p])
+            })
+            .collect();
+        let opps = engine().check_ladder_arbitrage(&markets, &settings(StrategyKind::Ladder));
+        assert_eq!(opps.len(), 1);
+
+        let opp = &opps[0];
+        let mut held: Vec<(&str, Side)> = opp.legs.iter().map(|l| (l.market_key.as_str(), l.side)).collect();
+        held.sort_by_key(|(key, _)| *key);
+        assert_eq!(held, vec![("kalshi:a", Side::Yes), ("kalshi:b", Side::No)]);
+        // 1% Kalshi fee on the $0.90 basket
+        assert!((opp.net_profit_after_fees - (1.0 - 0.9 * 1.01)).abs() < 1e-9);
+        assert!(opp.description.contains("non-monotone"), "{}", opp.description);
+    }
+
+    #[test]
+    fn inverted_calendar_is_an_arb() {
+        // "By June" priced under "by March": YES June + NO March pays at least $1
+        let markets: Vec<Market> = [("a", "March 31", 0.50), ("b", "June 30", 0.40)].iter()
+            .map(|&(id, deadline, p)| Market {
+                close_date: Some("2025-12-31".to_string()),
+                ..Market::test("kalshi", id, &format!("Will Bitcoin reach $150,000 by {}?", deadline), &[p, 1.0 - p])
+            })
+            .collect();
+        let opps = engine().check_calendar_arbitrage(&markets, &settings(StrategyKind::Calendar));
+        assert_eq!(opps.len(), 1);
+
+        let opp = &opps[0];
+        let mut held: Vec<(&str, Side)> = opp.legs.iter().map(|l| (l.market_key.as_str(), l.side)).collect();
+        held.sort_by_key(|(key, _)| *key);
+        assert_eq!(held, vec![("kalshi:a", Side::No), ("kalshi:b", Side::Yes)]);
+        assert!((opp.net_profit_after_fees - (1.0 - 0.9 * 1.01)).abs() < 1e-9);
+        assert!(opp.description.contains("1 inverted window(s)"), "{}", opp.description);
+    }
+
+    #[test]
+    fn compounds_link_to_their_clauses() {
+        let questions = [
+            "Will Bitcoin reach $150,000 and Ethereum reach $10,000 by June 30?",
+            "Will Bitcoin reach $150,000 by June 30?",
+            "Will Ethereum reach $10,000 by June 30?",
+            "Will Bitcoin reach $150,000 or Ethereum reach $10,000 by June 30?",
+            "yes Lakers, no Celtics",
+            "Will the Celtics win?",
+        ];
+        let markets: Vec<Market> = questions.iter().enumerate()
+            .map(|(i, q)| Market {
+                close_date: Some("2025-12-31".to_string()),
+                ..Market::test("polymarket", &i.to_string(), q, &[0.5, 0.5])
+            })
+            .collect();
+
+        let deps = engine().detect_dependencies(&markets);
+        let mut links: Vec<(usize, usize, &str)> = deps.iter()
+            .filter(|d| ["conjunction", "disjunction", "exhaustive"].contains(&d.dependency_type.as_str())
+                || (d.dependency_type == "mutually_exclusive" && d.implying_market == 4))
+            .map(|d| (d.implying_market, d.implied_market, d.dependency_type.as_str()))
+            .collect();
+        links.sort_unstable();
+        assert_eq!(links, vec![
+            // "A and B" implies each clause
+            (0, 1, "conjunction"),
+            (0, 2, "conjunction"),
+            // Each clause implies "A or B"
+            (1, 3, "disjunction"),
+            (2, 3, "disjunction"),
+            // The parlay needs the Celtics not to win
+            (4, 5, "mutually_exclusive"),
+        ]);
+    }
+
+    #[test]
+    fn truncate_text_cuts_on_char_boundaries() {
+        let engine = engine();
+        assert_eq!(engine.truncate_text("“Pelé” wins", 3), "“Pe...");
+        assert_eq!(engine.truncate_text("short", 25), "short");
+    }
+}
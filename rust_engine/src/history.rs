@@ -0,0 +1,437 @@
+use crate::config::HistoryConfig;
+use crate::engine::Market;
+use crate::store::migrate;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+
+const MIGRATIONS: &[&str] = &[
+    // 1: markets and their quote points
+    "CREATE TABLE markets (
+        id INTEGER PRIMARY KEY,
+        key TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE points (
+        market INTEGER NOT NULL REFERENCES markets (id),
+        ts INTEGER NOT NULL,
+        prices TEXT NOT NULL,
+        best_bid REAL,
+        best_ask REAL,
+        liquidity REAL NOT NULL,
+        PRIMARY KEY (market, ts)
+    ) WITHOUT ROWID;",
+    // 2: what backtests need to rebuild whole scans: the quote fields the market
+    // filters read, scan times, listing spans and metadata versions
+    "ALTER TABLE points ADD COLUMN volume REAL;
+    ALTER TABLE points ADD COLUMN last_trade INTEGER;
+    ALTER TABLE points ADD COLUMN top_of_book REAL;
+    ALTER TABLE points ADD COLUMN venue_ts INTEGER;
+    CREATE TABLE scans (
+        ts INTEGER PRIMARY KEY
+    );
+    CREATE TABLE listings (
+        market INTEGER NOT NULL REFERENCES markets (id),
+        listed_from INTEGER NOT NULL,
+        listed_to INTEGER NOT NULL,
+        PRIMARY KEY (market, listed_from)
+    ) WITHOUT ROWID;
+    CREATE TABLE market_meta (
+        market INTEGER NOT NULL REFERENCES markets (id),
+        ts INTEGER NOT NULL,
+        meta TEXT NOT NULL,
+        PRIMARY KEY (market, ts)
+    ) WITHOUT ROWID;",
+];
+
+/// One market's quote at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PricePoint {
+    pub at: DateTime<Utc>,
+    /// Parallel to the market's outcomes
+    pub prices: Vec<f64>,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub liquidity: f64,
+    pub volume: Option<f64>,
+    pub last_trade_time: Option<DateTime<Utc>>,
+    pub top_of_book_size: Option<f64>,
+    pub venue_time: Option<DateTime<Utc>>,
+}
+
+impl PricePoint {
+    fn from_market(market: &Market, at: DateTime<Utc>) -> Self {
+        Self {
+            at,
+            prices: market.outcome_prices.clone(),
+            best_bid: market.best_bid,
+            best_ask: market.best_ask,
+            liquidity: market.liquidity,
+            volume: market.volume,
+            last_trade_time: market.last_trade_time,
+            top_of_book_size: market.top_of_book_size,
+            venue_time: market.venue_time,
+        }
+    }
+
+    fn same_quote(&self, other: &PricePoint) -> bool {
+        self.prices == other.prices && self.best_bid == other.best_bid
+            && self.best_ask == other.best_ask && self.liquidity == other.liquidity
+            && self.volume == other.volume && self.last_trade_time == other.last_trade_time
+            && self.top_of_book_size == other.top_of_book_size && self.venue_time == other.venue_time
+    }
+
+    /// Put this quote on a market rebuilt from its metadata
+    fn apply(self, market: &mut Market) {
+        market.outcome_prices = self.prices;
+        market.best_bid = self.best_bid;
+        market.best_ask = self.best_ask;
+        market.spread = self.best_bid.zip(self.best_ask).map(|(bid, ask)| ask - bid);
+        market.liquidity = self.liquidity;
+        market.volume = self.volume;
+        market.last_trade_time = self.last_trade_time;
+        market.top_of_book_size = self.top_of_book_size;
+        market.venue_time = self.venue_time;
+    }
+}
+
+const POINT_COLUMNS: &str = "p.ts, p.prices, p.best_bid, p.best_ask, p.liquidity, p.volume, p.last_trade, p.top_of_book, p.venue_ts";
+
+/// Reads `POINT_COLUMNS` starting at column `first`
+fn point_from_row(row: &rusqlite::Row, first: usize) -> Result<PricePoint, Box<dyn Error>> {
+    Ok(PricePoint {
+        at: timestamp(row.get(first)?)?,
+        prices: split_prices(&row.get::<_, String>(first + 1)?)?,
+        best_bid: row.get(first + 2)?,
+        best_ask: row.get(first + 3)?,
+        liquidity: row.get(first + 4)?,
+        volume: row.get(first + 5)?,
+        last_trade_time: row.get::<_, Option<i64>>(first + 6)?.map(timestamp).transpose()?,
+        top_of_book_size: row.get(first + 7)?,
+        venue_time: row.get::<_, Option<i64>>(first + 8)?.map(timestamp).transpose()?,
+    })
+}
+
+fn timestamp(ts: i64) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    Ok(Utc.timestamp_opt(ts, 0).single().ok_or("bad timestamp")?)
+}
+
+/// A market with its quote fields cleared, stored once per change
+fn static_part(market: &Market) -> Market {
+    Market {
+        outcome_prices: Vec::new(),
+        liquidity: 0.0,
+        volume: None,
+        spread: None,
+        best_bid: None,
+        best_ask: None,
+        last_trade_time: None,
+        top_of_book_size: None,
+        fetched_at: None,
+        venue_time: None,
+        ..market.clone()
+    }
+}
+
+/// Append-only per-market price series in SQLite.
+///
+/// A point is only written when the market's quote changed since its last
+/// point, so a series is a step function: the quote at time `t` is the latest
+/// point at or before `t`. Points older than `full_resolution_hours` are thinned
+/// to the last one per `downsample_minutes` bucket.
+///
+/// Each scan's time is kept too, along with every market's listing spans (runs
+/// of consecutive scans it appeared in) and each version of its static fields,
+/// so whole scans can be rebuilt with `snapshot`.
+pub struct PriceHistory {
+    conn: Connection,
+    market_ids: HashMap<String, i64>,
+    last: HashMap<i64, PricePoint>,
+    meta: HashMap<i64, String>,
+    /// Start of each market's latest listing span and the scan it was last seen in
+    listings: HashMap<i64, (i64, i64)>,
+    last_scan: Option<i64>,
+}
+
+impl PriceHistory {
+    pub fn open(path: &str) -> Result<Self, Box<dyn Error>> {
+        let mut conn = Connection::open(path)?;
+        migrate(&mut conn, MIGRATIONS, "Price history")?;
+        let last_scan = conn.query_row("SELECT MAX(ts) FROM scans", [], |row| row.get(0))?;
+        Ok(Self {
+            conn,
+            market_ids: HashMap::new(),
+            last: HashMap::new(),
+            meta: HashMap::new(),
+            listings: HashMap::new(),
+            last_scan,
+        })
+    }
+
+    /// Record one scan; returns how many markets got a new point
+    pub fn record<'a>(&mut self, markets: impl Iterator<Item = &'a Market>, at: DateTime<Utc>) -> Result<usize, Box<dyn Error>> {
+        let ts = at.timestamp();
+        let tx = self.conn.transaction()?;
+        let mut written = 0;
+        {
+            let mut insert_market = tx.prepare_cached("INSERT OR IGNORE INTO markets (key) VALUES (?1)")?;
+            let mut market_id = tx.prepare_cached("SELECT id FROM markets WHERE key = ?1")?;
+            let mut latest_listing = tx.prepare_cached(
+                "SELECT listed_from, listed_to FROM listings WHERE market = ?1 ORDER BY listed_from DESC LIMIT 1",
+            )?;
+            let mut latest_meta = tx.prepare_cached(
+                "SELECT meta FROM market_meta WHERE market = ?1 ORDER BY ts DESC LIMIT 1",
+            )?;
+            let mut insert_point = tx.prepare_cached(
+                "INSERT OR REPLACE INTO points (market, ts, prices, best_bid, best_ask, liquidity, volume, last_trade, top_of_book, venue_ts)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            let mut upsert_listing = tx.prepare_cached(
+                "INSERT INTO listings (market, listed_from, listed_to) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (market, listed_from) DO UPDATE SET listed_to = excluded.listed_to",
+            )?;
+            let mut insert_meta = tx.prepare_cached("INSERT OR REPLACE INTO market_meta (market, ts, meta) VALUES (?1, ?2, ?3)")?;
+            tx.execute("INSERT OR IGNORE INTO scans (ts) VALUES (?1)", [ts])?;
+
+            for market in markets {
+                let key = market.key();
+                let id = match self.market_ids.get(&key) {
+                    Some(id) => *id,
+                    None => {
+                        insert_market.execute([&key])?;
+                        let id: i64 = market_id.query_row([&key], |row| row.get(0))?;
+                        if let Some(listing) = latest_listing.query_row([id], |row| Ok((row.get(0)?, row.get(1)?))).optional()? {
+                            self.listings.insert(id, listing);
+                        }
+                        if let Some(meta) = latest_meta.query_row([id], |row| row.get(0)).optional()? {
+                            self.meta.insert(id, meta);
+                        }
+                        self.market_ids.insert(key, id);
+                        id
+                    }
+                };
+
+                // A market missing from the previous scan starts a new listing span
+                let listed_from = match self.listings.get(&id) {
+                    Some(&(from, to)) if to == ts || Some(to) == self.last_scan => from,
+                    _ => ts,
+                };
+                upsert_listing.execute(params![id, listed_from, ts])?;
+                self.listings.insert(id, (listed_from, ts));
+
+                let meta = serde_json::to_string(&static_part(market))?;
+                if self.meta.get(&id) != Some(&meta) {
+                    insert_meta.execute(params![id, ts, meta])?;
+                    self.meta.insert(id, meta);
+                }
+
+                let point = PricePoint::from_market(market, at);
+                if self.last.get(&id).is_some_and(|last| last.same_quote(&point)) {
+                    continue;
+                }
+                insert_point.execute(params![
+                    id,
+                    ts,
+                    join_prices(&point.prices),
+                    point.best_bid,
+                    point.best_ask,
+                    point.liquidity,
+                    point.volume,
+                    point.last_trade_time.map(|t| t.timestamp()),
+                    point.top_of_book_size,
+                    point.venue_time.map(|t| t.timestamp()),
+                ])?;
+                self.last.insert(id, point);
+                written += 1;
+            }
+        }
+        tx.commit()?;
+        self.last_scan = Some(self.last_scan.map_or(ts, |last| last.max(ts)));
+        Ok(written)
+    }
+
+    /// Points for one market between `from` and `to`, starting with the quote in effect at `from`
+    pub fn query(&self, market_key: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<PricePoint>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM points p JOIN markets m ON m.id = p.market
+             WHERE m.key = ?1 AND p.ts <= ?3 AND p.ts >= COALESCE(
+                (SELECT MAX(ts) FROM points WHERE market = m.id AND ts <= ?2), ?2)
+             ORDER BY p.ts",
+            POINT_COLUMNS,
+        ))?;
+        let mut rows = stmt.query(params![market_key, from.timestamp(), to.timestamp()])?;
+        let mut points = Vec::new();
+        while let Some(row) = rows.next()? {
+            points.push(point_from_row(row, 0)?);
+        }
+        Ok(points)
+    }
+
+    /// Downsample old points and drop those past retention; returns rows deleted
+    pub fn compact(&mut self, config: &HistoryConfig, now: DateTime<Utc>) -> Result<usize, Box<dyn Error>> {
+        let full_resolution_cutoff = (now - Duration::minutes((config.full_resolution_hours * 60.0) as i64)).timestamp();
+        let retention_cutoff = (now - Duration::days(config.retention_days as i64)).timestamp();
+        let bucket = (config.downsample_minutes.max(1) as i64) * 60;
+
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM listings WHERE listed_to < ?1", [retention_cutoff])?;
+        // Keep the point and metadata version in effect at the cutoff for markets still listed then
+        let mut deleted = tx.execute(
+            "DELETE FROM points WHERE ts < ?1 AND (
+                market NOT IN (SELECT market FROM listings)
+                OR ts < (SELECT MAX(ts) FROM points newer WHERE newer.market = points.market AND newer.ts <= ?1))",
+            [retention_cutoff],
+        )?;
+        tx.execute(
+            "DELETE FROM market_meta WHERE ts < ?1 AND (
+                market NOT IN (SELECT market FROM listings)
+                OR ts < (SELECT MAX(ts) FROM market_meta newer WHERE newer.market = market_meta.market AND newer.ts <= ?1))",
+            [retention_cutoff],
+        )?;
+        deleted += tx.execute(
+            "DELETE FROM points WHERE ts < ?1 AND ts >= ?3 AND (market, ts) NOT IN (
+                SELECT market, MAX(ts) FROM points WHERE ts < ?1 GROUP BY market, ts / ?2)",
+            params![full_resolution_cutoff, bucket, retention_cutoff],
+        )?;
+        // Scans are thinned the same way so old replays step once per bucket
+        tx.execute("DELETE FROM scans WHERE ts < ?1", [retention_cutoff])?;
+        tx.execute(
+            "DELETE FROM scans WHERE ts < ?1 AND ts NOT IN (SELECT MAX(ts) FROM scans WHERE ts < ?1 GROUP BY ts / ?2)",
+            params![full_resolution_cutoff, bucket],
+        )?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    /// Recorded scan times in a range, oldest first
+    pub fn scans(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<DateTime<Utc>>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare("SELECT ts FROM scans WHERE ts >= ?1 AND ts <= ?2 ORDER BY ts")?;
+        let times = stmt.query_map([from.timestamp(), to.timestamp()], |row| row.get::<_, i64>(0))?
+            .map(|ts| timestamp(ts?))
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+        Ok(times)
+    }
+
+    /// Every market listed at `at`, with the metadata and quote in effect then
+    pub fn snapshot(&self, at: DateTime<Utc>) -> Result<Vec<Market>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT mm.meta, {} FROM listings l
+             JOIN points p ON p.market = l.market
+                AND p.ts = (SELECT MAX(ts) FROM points WHERE market = l.market AND ts <= ?1)
+             JOIN market_meta mm ON mm.market = l.market
+                AND mm.ts = (SELECT MAX(ts) FROM market_meta WHERE market = l.market AND ts <= ?1)
+             WHERE l.listed_from <= ?1 AND l.listed_to >= ?1",
+            POINT_COLUMNS,
+        ))?;
+        let mut rows = stmt.query([at.timestamp()])?;
+        let mut markets = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut market: Market = serde_json::from_str(&row.get::<_, String>(0)?)?;
+            point_from_row(row, 1)?.apply(&mut market);
+            market.fetched_at = Some(at);
+            markets.push(market);
+        }
+        Ok(markets)
+    }
+}
+
+fn join_prices(prices: &[f64]) -> String {
+    prices.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn split_prices(text: &str) -> Result<Vec<f64>, Box<dyn Error>> {
+    if text.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(text.split(',').map(str::parse).collect::<Result<Vec<f64>, _>>()?)
+}
+
+/// `history <market_key> [hours]`: print the recorded series
+pub fn print_series(config: &HistoryConfig, market_key: &str, hours: f64) -> Result<(), Box<dyn Error>> {
+    let history = PriceHistory::open(&config.path)?;
+    let to = Utc::now();
+    let from = to - Duration::minutes((hours * 60.0) as i64);
+    let points = history.query(market_key, from, to)?;
+    if points.is_empty() {
+        println!("No history for {} in the last {}h", market_key, hours);
+        return Ok(());
+    }
+
+    println!("{:<20} {:<16} {:>7} {:>7} {:>12}", "time (UTC)", "prices", "bid", "ask", "liquidity");
+    let quote = |q: Option<f64>| q.map(|v| format!("{:.3}", v)).unwrap_or_else(|| "-".to_string());
+    for p in &points {
+        let prices: Vec<String> = p.prices.iter().map(|v| format!("{:.3}", v)).collect();
+        println!("{:<20} {:<16} {:>7} {:>7} {:>12.0}",
+            p.at.format("%Y-%m-%d %H:%M:%S"), prices.join("/"), quote(p.best_bid), quote(p.best_ask), p.liquidity);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn snapshots_follow_listing_spans_and_metadata_versions() {
+        let mut history = PriceHistory::open(":memory:").unwrap();
+        let mut market = Market::test("Kalshi", "KXA", "Will A happen?", &[0.4, 0.6]);
+        market.close_date = Some("2030-01-01".to_string());
+        market.volume = Some(500.0);
+        market.last_trade_time = Some(at(-60));
+        market.top_of_book_size = Some(25.0);
+
+        history.record([&market].into_iter(), at(0)).unwrap();
+        history.record([].into_iter(), at(1)).unwrap();
+        market.close_date = Some("2031-01-01".to_string());
+        history.record([&market].into_iter(), at(2)).unwrap();
+
+        let first = history.snapshot(at(0)).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].close_date.as_deref(), Some("2030-01-01"));
+        assert_eq!(first[0].volume, Some(500.0));
+        assert_eq!(first[0].last_trade_time, Some(at(-60)));
+        assert_eq!(first[0].top_of_book_size, Some(25.0));
+
+        // Delisted for one scan, then relisted with new rules
+        assert!(history.snapshot(at(1)).unwrap().is_empty());
+        let relisted = history.snapshot(at(2)).unwrap();
+        assert_eq!(relisted[0].close_date.as_deref(), Some("2031-01-01"));
+        assert_eq!(relisted[0].outcome_prices, vec![0.4, 0.6]);
+    }
+
+    #[test]
+    fn compaction_keeps_the_quote_in_effect_at_the_cutoff() {
+        let mut history = PriceHistory::open(":memory:").unwrap();
+        let steady = Market::test("Kalshi", "STEADY", "Will it stay put?", &[0.3, 0.7]);
+        let mut moving = Market::test("Kalshi", "MOVING", "Will it move?", &[0.5, 0.5]);
+        let gone = Market::test("Kalshi", "GONE", "Delisted long ago?", &[0.2, 0.8]);
+
+        history.record([&steady, &moving, &gone].into_iter(), at(0)).unwrap();
+        moving.outcome_prices = vec![0.55, 0.45];
+        history.record([&steady, &moving].into_iter(), at(60)).unwrap();
+        for day in 1..=3 {
+            history.record([&steady, &moving].into_iter(), at(day * 24 * 60)).unwrap();
+        }
+
+        let config = HistoryConfig { retention_days: 2, full_resolution_hours: 1.0, ..HistoryConfig::default() };
+        let now = at(3 * 24 * 60);
+        history.compact(&config, now).unwrap();
+
+        let cutoff = now - Duration::days(2);
+        let markets = history.snapshot(cutoff).unwrap();
+        let mut prices: Vec<(String, Vec<f64>)> = markets.into_iter().map(|m| (m.id, m.outcome_prices)).collect();
+        prices.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(prices, vec![
+            ("MOVING".to_string(), vec![0.55, 0.45]),
+            ("STEADY".to_string(), vec![0.3, 0.7]),
+        ]);
+        assert!(history.query("Kalshi:GONE", at(0), now).unwrap().is_empty());
+        // Only the point in effect at the cutoff survives from before it
+        assert_eq!(history.query("Kalshi:MOVING", at(0), cutoff).unwrap().len(), 1);
+    }
+}
@@ -1,8 +1,13 @@
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use reqwest::Client;
 use std::error::Error;
 use crate::config::Config;
-use chrono::Utc;
+use crate::market_filter::DepthNeeds;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+
+/// Orderbook/trades requests in flight at once
+const DEPTH_CONCURRENCY: usize = 8;
 
 #[derive(Debug, Deserialize)]
 struct KalshiResponse {
@@ -10,7 +15,50 @@ struct KalshiResponse {
     cursor: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct KalshiMarketResponse {
+    market: KalshiMarket,
+}
+
+#[derive(Debug, Deserialize)]
+struct KalshiOrderbookResponse {
+    orderbook: KalshiOrderbook,
+}
+
+/// Resting bids per side as [price in cents, contracts], best (highest) last
 #[derive(Debug, Deserialize, Default)]
+struct KalshiOrderbook {
+    #[serde(default)]
+    yes: Option<Vec<[f64; 2]>>,
+    #[serde(default)]
+    no: Option<Vec<[f64; 2]>>,
+}
+
+impl KalshiOrderbook {
+    /// Contracts available at the best YES ask and best NO ask, whichever is smaller.
+    /// A YES ask is a resting NO bid (and vice versa).
+    fn top_of_book_size(&self) -> f64 {
+        let best = |bids: &Option<Vec<[f64; 2]>>| bids.as_ref()
+            .and_then(|levels| levels.iter().max_by(|a, b| a[0].total_cmp(&b[0])))
+            .map(|level| level[1])
+            .unwrap_or(0.0);
+        best(&self.yes).min(best(&self.no))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct KalshiTradesResponse {
+    #[serde(default)]
+    trades: Vec<KalshiTrade>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KalshiTrade {
+    created_time: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[allow(dead_code)] // Mirrors the API payload; not every field is consumed yet
 struct KalshiMarket {
     #[serde(default)]
     ticker: String,
@@ -30,6 +78,9 @@ struct KalshiMarket {
     no_ask: Option<f64>,
     #[serde(default)]
     volume: Option<f64>,
+    /// Resting order value, in cents
+    #[serde(default)]
+    liquidity: Option<f64>,
     #[serde(default)]
     status: String,
     #[serde(default)]
@@ -38,6 +89,10 @@ struct KalshiMarket {
     close_time: Option<String>,
     #[serde(default)]
     expiration_time: Option<String>,
+    #[serde(default)]
+    rules_primary: Option<String>,
+    #[serde(default)]
+    rules_secondary: Option<String>,
 }
 
 pub struct KalshiFetcher {
@@ -90,11 +145,12 @@ impl KalshiFetcher {
                 .json()
                 .await?;
 
+            let received = Utc::now();
             println!("[Kalshi] Received {} markets", response.markets.len());
 
             for market in response.markets {
                 // Filter expired
-                let close_date = market.close_time.or(market.expiration_time);
+                let close_date = market.close_time.clone().or(market.expiration_time.clone());
                 if let Some(ref cd) = close_date {
                     if let Ok(dt) = cd.parse::<chrono::DateTime<Utc>>() {
                         if dt < Utc::now() {
@@ -119,23 +175,7 @@ impl KalshiFetcher {
                     }
                 }
 
-                let yes_price = market.yes_ask.or(market.yes_bid).unwrap_or(0.0) / 100.0;
-                let no_price = market.no_ask.or(market.no_bid).unwrap_or(0.0) / 100.0;
-
-                let liquidity = market.volume.unwrap_or(0.0);
-
-                all_markets.push(crate::engine::Market {
-                    id: market.ticker.clone(),
-                    question: None,
-                    title: Some(market.title),
-                    subtitle: market.subtitle,
-                    outcome_prices: vec![yes_price, no_price],
-                    platform: "Kalshi".to_string(),
-                    liquidity,
-                    close_date,
-                    url: Some(format!("https://kalshi.com/markets/{}", 
-                        market.event_ticker.as_deref().unwrap_or(&market.ticker))),
-                });
+                all_markets.push(to_market(market, close_date, received));
             }
 
             cursor = response.cursor;
@@ -148,4 +188,148 @@ impl KalshiFetcher {
         println!("[Kalshi] Total: {} markets fetched (filtered)", all_markets.len());
         Ok(all_markets)
     }
+
+    /// Fill in top-of-book size and last trade time where the filters need them,
+    /// one orderbook and one trades request per market. Failures leave the field unset.
+    pub async fn fetch_depth(&self, markets: &mut [crate::engine::Market], needs: impl Fn(&crate::engine::Market) -> DepthNeeds) {
+        let wanted: Vec<(&mut crate::engine::Market, DepthNeeds)> = markets.iter_mut()
+            .map(|m| { let n = needs(m); (m, n) })
+            .filter(|(_, n)| n.any())
+            .collect();
+        if wanted.is_empty() {
+            return;
+        }
+        let requested = wanted.len();
+
+        let failures: usize = stream::iter(wanted)
+            .map(|(market, needs)| async move {
+                let mut failed = 0;
+                if needs.book {
+                    match self.fetch_orderbook(&market.id).await {
+                        Ok(book) => market.top_of_book_size = Some(book.top_of_book_size()),
+                        Err(_) => failed = 1,
+                    }
+                }
+                if needs.last_trade {
+                    match self.fetch_last_trade(&market.id).await {
+                        Ok(last) => market.last_trade_time = last,
+                        Err(_) => failed = 1,
+                    }
+                }
+                failed
+            })
+            .buffer_unordered(DEPTH_CONCURRENCY)
+            .fold(0, |total, failed| async move { total + failed })
+            .await;
+
+        println!("[Kalshi] Book depth for {} markets ({} failed)", requested, failures);
+    }
+
+    async fn fetch_orderbook(&self, ticker: &str) -> Result<KalshiOrderbook, Box<dyn Error>> {
+        let url = format!("{}/markets/{}/orderbook", self.base_url, ticker);
+        let response: KalshiOrderbookResponse = self.client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.orderbook)
+    }
+
+    /// Time of the latest trade; None if the market never traded
+    async fn fetch_last_trade(&self, ticker: &str) -> Result<Option<DateTime<Utc>>, Box<dyn Error>> {
+        let url = format!("{}/markets/trades?ticker={}&limit=1", self.base_url, ticker);
+        let response: KalshiTradesResponse = self.client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.trades.first().and_then(|t| t.created_time.parse::<DateTime<Utc>>().ok()))
+    }
+
+    /// Refetch a single market by ticker (pre-alert revalidation)
+    pub async fn fetch_market(&self, ticker: &str) -> Result<crate::engine::Market, Box<dyn Error>> {
+        let url = format!("{}/markets/{}", self.base_url, ticker);
+        let response: KalshiMarketResponse = self.client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let market = response.market;
+        let close_date = market.close_time.clone().or(market.expiration_time.clone());
+        Ok(to_market(market, close_date, Utc::now()))
+    }
+}
+
+/// Convert an API market into the engine's market type
+fn to_market(market: KalshiMarket, close_date: Option<String>, received: DateTime<Utc>) -> crate::engine::Market {
+    let yes_price = market.yes_ask.or(market.yes_bid).unwrap_or(0.0) / 100.0;
+    let no_price = market.no_ask.or(market.no_bid).unwrap_or(0.0) / 100.0;
+
+    let liquidity = market.liquidity.map(|c| c / 100.0)
+        .or(market.volume)
+        .unwrap_or(0.0);
+
+    // Kalshi quotes 0 (or 100 on the ask) for an empty side
+    let quoted = |ask: Option<f64>| ask.is_some_and(|a| a > 0.0 && a < 100.0);
+    let spread = match (market.yes_bid, market.yes_ask) {
+        (Some(bid), Some(ask)) if bid > 0.0 && quoted(Some(ask)) => Some((ask - bid) / 100.0),
+        _ => None,
+    };
+    let top_of_book_size = (!quoted(market.yes_ask) || !quoted(market.no_ask)).then_some(0.0);
+
+    let rules = match (market.rules_primary, market.rules_secondary) {
+        (Some(primary), Some(secondary)) => Some(format!("{}\n{}", primary, secondary)),
+        (primary, secondary) => primary.or(secondary),
+    };
+
+    crate::engine::Market {
+        id: market.ticker.clone(),
+        question: None,
+        title: Some(market.title),
+        subtitle: market.subtitle,
+        outcome_prices: vec![yes_price, no_price],
+        outcomes: vec!["Yes".to_string(), "No".to_string()],
+        platform: "Kalshi".to_string(),
+        liquidity,
+        close_date,
+        url: Some(format!("https://kalshi.com/markets/{}", 
+            market.event_ticker.as_deref().unwrap_or(&market.ticker))),
+        rules,
+        resolution_source: None,
+        event_id: market.event_ticker,
+        currency: crate::engine::Currency::Usd,
+        volume: market.volume,
+        spread,
+        best_bid: market.yes_bid.filter(|b| *b > 0.0).map(|b| b / 100.0),
+        best_ask: market.yes_ask.filter(|a| quoted(Some(*a))).map(|a| a / 100.0),
+        last_trade_time: None,
+        top_of_book_size,
+        fetched_at: Some(received),
+        // The market payload carries no update time
+        venue_time: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_of_book_is_the_thinner_ask() {
+        let book: KalshiOrderbookResponse = serde_json::from_str(
+            r#"{"orderbook": {"yes": [[40, 500], [45, 30]], "no": [[50, 10], [52, 80]]}}"#,
+        ).unwrap();
+        // YES ask = best NO bid (52c, 80), NO ask = best YES bid (45c, 30)
+        assert_eq!(book.orderbook.top_of_book_size(), 30.0);
+
+        let one_sided: KalshiOrderbookResponse = serde_json::from_str(r#"{"orderbook": {"yes": [[45, 30]], "no": null}}"#).unwrap();
+        assert_eq!(one_sided.orderbook.top_of_book_size(), 0.0);
+    }
 }
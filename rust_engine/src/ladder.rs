@@ -0,0 +1,275 @@
+use crate::engine::Market;
+use crate::proposition::{Comparator, DeadlineKind, PropositionParser, ThresholdUnit};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+// Worlds are bitmasks over rungs
+pub const MAX_LADDER_RUNGS: usize = 32;
+
+// "$94,999.99" and "$95,000" are the same breakpoint
+const BREAKPOINT_TOLERANCE: f64 = 1e-4;
+
+// Bracket sums further than this from $1 are called out in the description
+const BRACKET_SUM_TOLERANCE: f64 = 0.02;
+
+/// One market on a ladder: YES iff the underlying value lands in [lower, upper)
+#[derive(Debug, Clone, Copy)]
+pub struct Rung {
+    pub market: usize,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Rung {
+    fn is_bracket(&self) -> bool {
+        self.lower.is_finite() && self.upper.is_finite()
+    }
+}
+
+/// Markets that all pay on the same underlying value at the same deadline,
+/// e.g. "BTC above 90k / 95k / 100k on June 30" plus a Kalshi bracket series
+#[derive(Debug, Clone)]
+pub struct Ladder {
+    pub subject: String,
+    pub deadline: NaiveDate,
+    pub rungs: Vec<Rung>,
+}
+
+impl Ladder {
+    /// One world per region between breakpoints; bit `i` is set when rung `i` pays
+    pub fn worlds(&self) -> Vec<u32> {
+        let mut bounds: Vec<f64> = self.rungs.iter()
+            .flat_map(|r| [r.lower, r.upper])
+            .filter(|b| b.is_finite())
+            .collect();
+        bounds.sort_by(|a, b| a.total_cmp(b));
+
+        // Collapse near-equal bounds onto the first of each cluster
+        let mut breakpoints: Vec<f64> = Vec::new();
+        for b in bounds {
+            match breakpoints.last() {
+                Some(&last) if b - last <= BREAKPOINT_TOLERANCE * last.abs().max(1.0) => {}
+                _ => breakpoints.push(b),
+            }
+        }
+        let snap = |v: f64| {
+            if !v.is_finite() {
+                return v;
+            }
+            breakpoints.iter().rev()
+                .find(|&&b| b <= v + BREAKPOINT_TOLERANCE * v.abs().max(1.0))
+                .copied()
+                .unwrap_or(v)
+        };
+        let snapped: Vec<(f64, f64)> = self.rungs.iter().map(|r| (snap(r.lower), snap(r.upper))).collect();
+
+        let mut worlds: Vec<u32> = std::iter::once(f64::NEG_INFINITY)
+            .chain(breakpoints.iter().copied())
+            .map(|point| snapped.iter().enumerate()
+                .filter(|(_, (lower, upper))| *lower <= point && point < *upper)
+                .fold(0u32, |world, (i, _)| world | (1 << i)))
+            .collect();
+        worlds.sort_unstable();
+        worlds.dedup();
+        worlds
+    }
+
+    /// Short reason the ladder is mispriced, for the alert
+    pub fn violation(&self, markets: &[Market]) -> String {
+        let yes = |r: &Rung| markets[r.market].outcome_prices[0];
+
+        // P(value >= X) must not rise with X, P(value < X) must not fall
+        let above = self.rungs.iter().filter(|r| r.upper.is_infinite() && r.lower.is_finite());
+        for low in above.clone() {
+            if let Some(high) = above.clone().find(|r| r.lower > low.lower && yes(r) > yes(low)) {
+                return format!(
+                    "non-monotone: ≥{} at {:.2} but ≥{} at {:.2}",
+                    format_bound(low.lower), yes(low), format_bound(high.lower), yes(high)
+                );
+            }
+        }
+        let below = self.rungs.iter().filter(|r| r.lower.is_infinite() && r.upper.is_finite());
+        for high in below.clone() {
+            if let Some(low) = below.clone().find(|r| r.upper < high.upper && yes(r) > yes(high)) {
+                return format!(
+                    "non-monotone: <{} at {:.2} but <{} at {:.2}",
+                    format_bound(low.upper), yes(low), format_bound(high.upper), yes(high)
+                );
+            }
+        }
+
+        // Bracket series on one event: disjoint brackets can't sum above $1,
+        // and a series covering every value can't sum below it
+        let mut by_event: HashMap<String, Vec<&Rung>> = HashMap::new();
+        for rung in self.rungs.iter().filter(|r| markets[r.market].event_id.is_some()) {
+            by_event.entry(markets[rung.market].event_key()).or_default().push(rung);
+        }
+        for series in by_event.values_mut().filter(|s| s.len() >= 2 && s.iter().any(|r| r.is_bracket())) {
+            series.sort_by(|a, b| a.lower.total_cmp(&b.lower));
+            let disjoint = series.windows(2).all(|p| p[0].upper <= p[1].lower);
+            let covers = disjoint
+                && series[0].lower.is_infinite()
+                && series[series.len() - 1].upper.is_infinite()
+                && series.windows(2).all(|p| p[1].lower - p[0].upper <= BREAKPOINT_TOLERANCE * p[1].lower.abs().max(1.0));
+            let sum: f64 = series.iter().map(|r| yes(r)).sum();
+            if (disjoint && sum > 1.0 + BRACKET_SUM_TOLERANCE) || (covers && sum < 1.0 - BRACKET_SUM_TOLERANCE) {
+                return format!("brackets sum to {:.2}", sum);
+            }
+        }
+
+        "brackets disagree with thresholds".to_string()
+    }
+}
+
+/// Group markets into ladders by parsed subject, deadline and unit.
+///
+/// Brackets ("$90,000 to $94,999.99") and thresholds ("above $95k") on the same
+/// value share a ladder; markets in one venue event (a Kalshi series) always do.
+/// "By" deadlines track the running max (or min), so those only ladder with
+/// thresholds of the same direction.
+pub fn assemble(parser: &PropositionParser, markets: &[Market], reference: impl Fn(&Market) -> Option<NaiveDate>) -> Vec<Ladder> {
+    let mut keyed: Vec<(usize, String, NaiveDate, Rung)> = Vec::new();
+
+    for (i, market) in markets.iter().enumerate() {
+        let prices = &market.outcome_prices;
+        if prices.len() != 2 || prices[0] < 0.01 || prices[1] < 0.01 {
+            continue;
+        }
+
+        let mut text = market.full_text().to_lowercase();
+        let bracket = parser.extract_bracket(&mut text);
+        let Some(prop) = parser.parse(&text, reference(market)) else {
+            continue;
+        };
+
+        let (rung, unit, direction) = match (bracket, prop.comparator, prop.threshold) {
+            (Some((lo, hi)), _, None) => (Rung { market: i, lower: lo.value, upper: hi.value }, lo.unit, "value"),
+            (None, Some(Comparator::Above), Some(t)) => (Rung { market: i, lower: t.value, upper: f64::INFINITY }, t.unit, "above"),
+            (None, Some(Comparator::Below), Some(t)) => (Rung { market: i, lower: f64::NEG_INFINITY, upper: t.value }, t.unit, "below"),
+            _ => continue,
+        };
+
+        let (deadline, kind) = match prop.deadline {
+            Some(d) => (d.date, d.kind),
+            None => match reference(market) {
+                Some(date) => (date, DeadlineKind::On),
+                None => continue,
+            },
+        };
+        let direction = match kind {
+            DeadlineKind::On => "value",
+            DeadlineKind::By if direction == "value" => continue,
+            DeadlineKind::By => direction,
+        };
+
+        let key = format!(
+            "{}|{}|{:?}|{}|{}",
+            prop.subject, deadline, kind, direction, unit == ThresholdUnit::Percent
+        );
+        keyed.push((i, key, deadline, rung));
+    }
+
+    // A venue event is one ladder even when its subtitles parse to different subjects
+    let mut event_keys: HashMap<String, String> = HashMap::new();
+    for (i, key, _, _) in &keyed {
+        if markets[*i].event_id.is_some() {
+            event_keys.entry(markets[*i].event_key()).or_insert_with(|| key.clone());
+        }
+    }
+
+    let mut ladders: HashMap<String, Ladder> = HashMap::new();
+    for (i, key, deadline, rung) in keyed {
+        let key = match markets[i].event_id {
+            Some(_) => event_keys[&markets[i].event_key()].clone(),
+            None => key,
+        };
+        let subject = key.split('|').next().unwrap_or_default().to_string();
+        ladders.entry(key)
+            .or_insert_with(|| Ladder { subject, deadline, rungs: Vec::new() })
+            .rungs.push(rung);
+    }
+
+    ladders.into_values()
+        .filter(|l| l.rungs.len() >= 2)
+        .map(|mut l| {
+            if l.rungs.len() > MAX_LADDER_RUNGS {
+                // Keep the rungs that can actually be traded
+                l.rungs.sort_by(|a, b| markets[b.market].liquidity.total_cmp(&markets[a.market].liquidity));
+                l.rungs.truncate(MAX_LADDER_RUNGS);
+            }
+            l.rungs.sort_by(|a, b| a.lower.total_cmp(&b.lower).then(a.upper.total_cmp(&b.upper)));
+            l
+        })
+        .collect()
+}
+
+fn format_bound(value: f64) -> String {
+    if value.abs() >= 1e3 && value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ladder(rungs: &[(f64, f64)]) -> Ladder {
+        Ladder {
+            subject: "bitcoin".to_string(),
+            deadline: NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+            rungs: rungs.iter().enumerate()
+                .map(|(market, &(lower, upper))| Rung { market, lower, upper })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn threshold_ladder_worlds_are_nested() {
+        let l = ladder(&[(90e3, f64::INFINITY), (95e3, f64::INFINITY), (100e3, f64::INFINITY)]);
+        assert_eq!(l.worlds(), vec![0b000, 0b001, 0b011, 0b111]);
+    }
+
+    #[test]
+    fn near_equal_breakpoints_collapse() {
+        // "$90,000 to $94,999.99" ends where "$95,000 or above" starts
+        let l = ladder(&[(90e3, 94_999.99), (95e3, f64::INFINITY)]);
+        assert_eq!(l.worlds(), vec![0b00, 0b01, 0b10]);
+    }
+
+    #[test]
+    fn violations_name_the_inconsistency() {
+        let l = ladder(&[(90e3, f64::INFINITY), (100e3, f64::INFINITY)]);
+        let markets = [
+            Market::test("kalshi", "a", "Will BTC be above 90k on June 30?", &[0.40, 0.60]),
+            Market::test("kalshi", "b", "Will BTC be above 100k on June 30?", &[0.50, 0.50]),
+        ];
+        assert_eq!(l.violation(&markets), "non-monotone: ≥90000 at 0.40 but ≥100000 at 0.50");
+
+        let l = ladder(&[(f64::NEG_INFINITY, 90e3), (90e3, 100e3), (100e3, f64::INFINITY)]);
+        let markets: Vec<Market> = [0.30, 0.45, 0.35].iter().enumerate()
+            .map(|(i, &p)| Market { event_id: Some("KXBTC".to_string()), ..Market::test("kalshi", &i.to_string(), "", &[p, 1.0 - p]) })
+            .collect();
+        assert_eq!(l.violation(&markets), "brackets sum to 1.10");
+    }
+
+    #[test]
+    fn assembles_by_subject_and_deadline() {
+        let markets = [
+            Market::test("kalshi", "a", "Will BTC be above 90k on June 30?", &[0.60, 0.40]),
+            Market::test("polymarket", "b", "Will BTC be above 100k on June 30?", &[0.40, 0.60]),
+            Market::test("kalshi", "c", "Will BTC be above 100k on July 31?", &[0.45, 0.55]),
+            Market::test("kalshi", "d", "Will ETH be above 5k on June 30?", &[0.20, 0.80]),
+        ];
+        let reference = NaiveDate::from_ymd_opt(2025, 3, 1);
+        let ladders = assemble(&PropositionParser::new(), &markets, |_| reference);
+
+        assert_eq!(ladders.len(), 1);
+        let l = &ladders[0];
+        assert_eq!(l.subject, "bitcoin");
+        assert_eq!(l.deadline, NaiveDate::from_ymd_opt(2025, 6, 30).unwrap());
+        let rungs: Vec<(usize, f64)> = l.rungs.iter().map(|r| (r.market, r.lower)).collect();
+        assert_eq!(rungs, vec![(0, 90e3), (1, 100e3)]);
+    }
+}
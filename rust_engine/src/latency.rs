@@ -0,0 +1,53 @@
+use crate::engine::Timing;
+
+/// One cycle's opportunity timings, reported as p50/p95
+#[derive(Debug, Default)]
+pub struct LatencyStats {
+    data_age: Vec<f64>,
+    analysis: Vec<f64>,
+    send: Vec<f64>,
+    end_to_end: Vec<f64>,
+}
+
+impl LatencyStats {
+    /// Add whatever the timing has; send and end-to-end are only set on alerted opportunities
+    pub fn record(&mut self, timing: &Timing) {
+        if let Some(age) = timing.data_age_ms {
+            self.data_age.push(age as f64);
+        }
+        self.analysis.push(timing.analysis_ms as f64);
+        if let Some(send) = timing.send_ms {
+            self.send.push(send as f64);
+        }
+        if let Some(e2e) = timing.end_to_end_ms {
+            self.end_to_end.push(e2e as f64);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.analysis.is_empty()
+    }
+
+    /// "data age 850/2100ms | analysis ..." as p50/p95, skipping empty series
+    pub fn describe(&self) -> String {
+        [
+            ("data age", &self.data_age),
+            ("analysis", &self.analysis),
+            ("send", &self.send),
+            ("end-to-end", &self.end_to_end),
+        ]
+            .into_iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|(name, samples)| format!("{} {:.0}/{:.0}ms", name, percentile(samples, 50.0), percentile(samples, 95.0)))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+/// Nearest-rank percentile
+fn percentile(samples: &[f64], p: f64) -> f64 {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
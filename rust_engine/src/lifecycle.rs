@@ -0,0 +1,221 @@
+use crate::config::LifecycleConfig;
+use crate::engine::Opportunity;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Opened,
+    /// ROI moved at least `min_roi_change_percent` since the last alert
+    Changed,
+    Closed,
+}
+
+impl EventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventKind::Opened => "opened",
+            EventKind::Changed => "changed",
+            EventKind::Closed => "closed",
+        }
+    }
+}
+
+/// One opportunity id followed across scans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedOpportunity {
+    /// Latest version seen in a scan
+    pub opportunity: Opportunity,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub peak_roi: f64,
+    pub current_roi: f64,
+    /// ROI in the last alert sent; `None` until the opening alert goes out
+    pub alerted_roi: Option<f64>,
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Consecutive scans the id was missing from
+    #[serde(skip)]
+    missed_scans: u32,
+}
+
+impl TrackedOpportunity {
+    /// Rebuild an open record loaded from the state store
+    pub fn restored(opportunity: Opportunity, first_seen: DateTime<Utc>, last_seen: DateTime<Utc>, peak_roi: f64, current_roi: f64, alerted_roi: Option<f64>) -> Self {
+        Self { opportunity, first_seen, last_seen, peak_roi, current_roi, alerted_roi, closed_at: None, missed_scans: 0 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LifecycleEvent {
+    pub kind: EventKind,
+    pub record: TrackedOpportunity,
+}
+
+/// Turns each scan's opportunity list into opened / changed / closed events.
+///
+/// An opportunity stays "opened" until its alert is confirmed with
+/// `mark_alerted`, so one that fails revalidation is offered again next scan.
+/// Ids that were never alerted close silently.
+pub struct OpportunityTracker {
+    open: HashMap<String, TrackedOpportunity>,
+    min_roi_change: f64,
+    close_after_missed_scans: u32,
+}
+
+impl OpportunityTracker {
+    pub fn new(config: &LifecycleConfig) -> Self {
+        Self {
+            open: HashMap::new(),
+            min_roi_change: config.min_roi_change_percent,
+            close_after_missed_scans: config.close_after_missed_scans.max(1),
+        }
+    }
+
+    /// Pick up thresholds from a reloaded config without losing tracked state
+    pub fn reconfigure(&mut self, config: &LifecycleConfig) {
+        self.min_roi_change = config.min_roi_change_percent;
+        self.close_after_missed_scans = config.close_after_missed_scans.max(1);
+    }
+
+    pub fn open_count(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Seed with records persisted before a restart so they don't re-alert as new
+    pub fn restore(&mut self, records: Vec<TrackedOpportunity>) {
+        for record in records {
+            self.open.insert(record.opportunity.id.clone(), record);
+        }
+    }
+
+    /// Open records that have been alerted (the ones worth persisting)
+    pub fn alerted(&self) -> impl Iterator<Item = &TrackedOpportunity> {
+        self.open.values().filter(|r| r.alerted_roi.is_some())
+    }
+
+    /// Fold one scan into the tracked set, in scan (rank) order
+    pub fn observe(&mut self, opportunities: &[Opportunity], now: DateTime<Utc>) -> Vec<LifecycleEvent> {
+        let mut events = Vec::new();
+
+        for opp in opportunities {
+            let record = self.open.entry(opp.id.clone()).or_insert_with(|| TrackedOpportunity {
+                opportunity: opp.clone(),
+                first_seen: now,
+                last_seen: now,
+                peak_roi: opp.roi_percent,
+                current_roi: opp.roi_percent,
+                alerted_roi: None,
+                closed_at: None,
+                missed_scans: 0,
+            });
+            record.opportunity = opp.clone();
+            record.last_seen = now;
+            record.current_roi = opp.roi_percent;
+            record.peak_roi = record.peak_roi.max(opp.roi_percent);
+            record.missed_scans = 0;
+
+            let kind = match record.alerted_roi {
+                None => Some(EventKind::Opened),
+                Some(alerted) if (opp.roi_percent - alerted).abs() >= self.min_roi_change => Some(EventKind::Changed),
+                Some(_) => None,
+            };
+            if let Some(kind) = kind {
+                events.push(LifecycleEvent { kind, record: record.clone() });
+            }
+        }
+
+        let seen: std::collections::HashSet<&str> = opportunities.iter().map(|o| o.id.as_str()).collect();
+        let mut closed = Vec::new();
+        for (id, record) in self.open.iter_mut().filter(|(id, _)| !seen.contains(id.as_str())) {
+            record.missed_scans += 1;
+            if record.missed_scans >= self.close_after_missed_scans {
+                closed.push(id.clone());
+            }
+        }
+        for id in closed {
+            if let Some(mut record) = self.open.remove(&id) {
+                if record.alerted_roi.is_some() {
+                    record.closed_at = Some(now);
+                    events.push(LifecycleEvent { kind: EventKind::Closed, record });
+                }
+            }
+        }
+
+        events
+    }
+
+    /// Record that an opened/changed alert went out at `roi`
+    pub fn mark_alerted(&mut self, id: &str, roi: f64) {
+        if let Some(record) = self.open.get_mut(id) {
+            record.alerted_roi = Some(roi);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn tracker() -> OpportunityTracker {
+        OpportunityTracker::new(&LifecycleConfig { min_roi_change_percent: 1.0, close_after_missed_scans: 2 })
+    }
+
+    fn kinds(events: &[LifecycleEvent]) -> Vec<(&str, EventKind)> {
+        events.iter().map(|e| (e.record.opportunity.id.as_str(), e.kind)).collect()
+    }
+
+    #[test]
+    fn opens_until_alerted_then_changes_on_material_moves() {
+        let mut tracker = tracker();
+        let t0 = Utc::now();
+
+        let events = tracker.observe(&[Opportunity::test("a", 5.0)], t0);
+        assert_eq!(kinds(&events), vec![("a", EventKind::Opened)]);
+
+        // Not alerted yet (e.g. faded on refetch): still opening
+        let events = tracker.observe(&[Opportunity::test("a", 5.2)], t0 + Duration::minutes(1));
+        assert_eq!(kinds(&events), vec![("a", EventKind::Opened)]);
+        tracker.mark_alerted("a", 5.2);
+
+        assert!(tracker.observe(&[Opportunity::test("a", 5.9)], t0 + Duration::minutes(2)).is_empty());
+
+        let events = tracker.observe(&[Opportunity::test("a", 7.0)], t0 + Duration::minutes(3));
+        assert_eq!(kinds(&events), vec![("a", EventKind::Changed)]);
+        let record = &events[0].record;
+        assert_eq!(record.first_seen, t0);
+        assert_eq!(record.peak_roi, 7.0);
+        assert_eq!(record.alerted_roi, Some(5.2));
+    }
+
+    #[test]
+    fn closes_alerted_ids_after_missed_scans() {
+        let mut tracker = tracker();
+        let t0 = Utc::now();
+        tracker.observe(&[Opportunity::test("alerted", 5.0), Opportunity::test("silent", 5.0)], t0);
+        tracker.mark_alerted("alerted", 5.0);
+
+        // One miss is tolerated, and seeing the id again resets the count
+        assert!(tracker.observe(&[], t0 + Duration::minutes(1)).is_empty());
+        assert!(tracker.observe(&[Opportunity::test("alerted", 5.0)], t0 + Duration::minutes(2)).is_empty());
+        assert!(tracker.observe(&[], t0 + Duration::minutes(3)).is_empty());
+
+        let closed_at = t0 + Duration::minutes(4);
+        let events = tracker.observe(&[], closed_at);
+        assert_eq!(kinds(&events), vec![("alerted", EventKind::Closed)]);
+        assert_eq!(events[0].record.closed_at, Some(closed_at));
+        assert_eq!(tracker.open_count(), 0);
+    }
+
+    #[test]
+    fn restored_records_do_not_reopen() {
+        let mut tracker = tracker();
+        let t0 = Utc::now();
+        tracker.restore(vec![TrackedOpportunity::restored(Opportunity::test("a", 5.0), t0, t0, 5.0, 5.0, Some(5.0))]);
+
+        assert!(tracker.observe(&[Opportunity::test("a", 5.5)], t0 + Duration::minutes(1)).is_empty());
+        assert_eq!(tracker.alerted().count(), 1);
+    }
+}
@@ -0,0 +1,187 @@
+use minilp::{ComparisonOp, OptimizationDirection, Problem};
+use serde::{Deserialize, Serialize};
+
+// Worlds are enumerated as bitmasks, so keep sets small enough to stay cheap
+pub const MAX_LP_MARKETS: usize = 12;
+
+/// Logical relation between markets (indices are local to the solver input)
+#[derive(Debug, Clone)]
+pub enum LogicalConstraint {
+    /// If market A resolves YES, market B must resolve YES
+    Implies(usize, usize),
+    /// Markets A and B can't both resolve YES
+    MutuallyExclusive(usize, usize),
+    /// At least one of the markets resolves YES
+    Exhaustive(Vec<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Yes,
+    No,
+}
+
+/// Per-share cost of each side, fees included
+#[derive(Debug, Clone, Copy)]
+pub struct LpMarket {
+    pub yes_cost: f64,
+    pub no_cost: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LpPosition {
+    pub market: usize,
+    pub side: Side,
+    pub shares: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LpSolution {
+    pub positions: Vec<LpPosition>,
+    pub total_cost: f64,
+    pub worst_case_payout: f64,
+    pub feasible_worlds: usize,
+}
+
+/// Enumerate every YES/NO assignment consistent with the constraints.
+/// Bit `i` of a world is set when market `i` resolves YES.
+pub fn feasible_worlds(n: usize, constraints: &[LogicalConstraint]) -> Vec<u32> {
+    if n == 0 || n > MAX_LP_MARKETS {
+        return Vec::new();
+    }
+
+    let is_yes = |world: u32, i: usize| world & (1 << i) != 0;
+
+    (0..(1u32 << n))
+        .filter(|&world| {
+            constraints.iter().all(|c| match c {
+                LogicalConstraint::Implies(a, b) => !is_yes(world, *a) || is_yes(world, *b),
+                LogicalConstraint::MutuallyExclusive(a, b) => !(is_yes(world, *a) && is_yes(world, *b)),
+                LogicalConstraint::Exhaustive(set) => set.iter().any(|&i| is_yes(world, i)),
+            })
+        })
+        .collect()
+}
+
+/// Find the cheapest YES/NO portfolio that pays at least $1 in every feasible world.
+///
+/// LP: minimize Σ yes_cost·y_i + no_cost·n_i
+///     s.t. Σ_{i YES in w} y_i + Σ_{i NO in w} n_i >= 1   for every feasible world w
+///          y_i, n_i >= 0
+///
+/// Any solution costing less than $1 is a guaranteed profit.
+pub fn solve(markets: &[LpMarket], constraints: &[LogicalConstraint]) -> Option<LpSolution> {
+    let worlds = feasible_worlds(markets.len(), constraints);
+    if worlds.is_empty() {
+        return None; // Contradictory constraints (or set too large)
+    }
+    solve_worlds(markets, &worlds)
+}
+
+/// Same LP over an explicit list of possible worlds (bit `i` set = market `i` YES),
+/// for callers that know the outcomes directly, like threshold ladders
+pub fn solve_worlds(markets: &[LpMarket], worlds: &[u32]) -> Option<LpSolution> {
+    if worlds.is_empty() || markets.is_empty() || markets.len() > 32 {
+        return None;
+    }
+
+    let mut problem = Problem::new(OptimizationDirection::Minimize);
+    let vars: Vec<_> = markets.iter()
+        .map(|m| (
+            problem.add_var(m.yes_cost, (0.0, f64::INFINITY)),
+            problem.add_var(m.no_cost, (0.0, f64::INFINITY)),
+        ))
+        .collect();
+
+    for &world in worlds {
+        let payout: Vec<_> = vars.iter().enumerate()
+            .map(|(i, (yes, no))| if world & (1 << i) != 0 { (*yes, 1.0) } else { (*no, 1.0) })
+            .collect();
+        problem.add_constraint(payout.as_slice(), ComparisonOp::Ge, 1.0);
+    }
+
+    let solution = problem.solve().ok()?;
+
+    let mut positions = Vec::new();
+    for (i, (yes, no)) in vars.iter().enumerate() {
+        for (var, side) in [(yes, Side::Yes), (no, Side::No)] {
+            let shares = solution[*var];
+            if shares > 1e-9 {
+                positions.push(LpPosition { market: i, side, shares });
+            }
+        }
+    }
+
+    let worst_case_payout = worlds.iter()
+        .map(|&world| positions.iter()
+            .filter(|p| (world & (1 << p.market) != 0) == (p.side == Side::Yes))
+            .map(|p| p.shares)
+            .sum::<f64>())
+        .fold(f64::INFINITY, f64::min);
+
+    Some(LpSolution {
+        positions,
+        total_cost: solution.objective(),
+        worst_case_payout,
+        feasible_worlds: worlds.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(yes_cost: f64, no_cost: f64) -> LpMarket {
+        LpMarket { yes_cost, no_cost }
+    }
+
+    #[test]
+    fn implies_chain_finds_cheapest_basket() {
+        // A ⇒ B ⇒ C, with C priced below A: NO A + YES C pays $1 in every world
+        let markets = [market(0.6, 0.4), market(0.5, 0.5), market(0.3, 0.7)];
+        let constraints = [LogicalConstraint::Implies(0, 1), LogicalConstraint::Implies(1, 2)];
+        assert_eq!(feasible_worlds(3, &constraints), vec![0b000, 0b100, 0b110, 0b111]);
+
+        let solution = solve(&markets, &constraints).unwrap();
+        assert_eq!(solution.feasible_worlds, 4);
+        assert!((solution.total_cost - 0.7).abs() < 1e-9);
+        assert!((solution.worst_case_payout - 1.0).abs() < 1e-9);
+
+        let mut held: Vec<(usize, Side)> = solution.positions.iter().map(|p| (p.market, p.side)).collect();
+        held.sort_by_key(|(market, _)| *market);
+        assert_eq!(held, vec![(0, Side::No), (2, Side::Yes)]);
+    }
+
+    #[test]
+    fn fairly_priced_chain_has_no_edge() {
+        let markets = [market(0.3, 0.7), market(0.5, 0.5), market(0.6, 0.4)];
+        let constraints = [LogicalConstraint::Implies(0, 1), LogicalConstraint::Implies(1, 2)];
+        let solution = solve(&markets, &constraints).unwrap();
+        assert!(solution.total_cost >= solution.worst_case_payout - 1e-9);
+    }
+
+    #[test]
+    fn contradictory_constraints_are_infeasible() {
+        // A must resolve YES and implies B, but A and B exclude each other
+        let markets = [market(0.5, 0.5), market(0.5, 0.5), market(0.5, 0.5)];
+        let constraints = [
+            LogicalConstraint::Exhaustive(vec![0]),
+            LogicalConstraint::Implies(0, 1),
+            LogicalConstraint::MutuallyExclusive(0, 1),
+        ];
+        assert!(feasible_worlds(3, &constraints).is_empty());
+        assert!(solve(&markets, &constraints).is_none());
+    }
+
+    #[test]
+    fn explicit_worlds_and_limits() {
+        // Brackets: exactly one of three resolves YES, and the YES side sums to $0.90
+        let markets = [market(0.3, 0.7), market(0.3, 0.7), market(0.3, 0.7)];
+        let solution = solve_worlds(&markets, &[0b001, 0b010, 0b100]).unwrap();
+        assert!((solution.total_cost - 0.9).abs() < 1e-9);
+        assert!((solution.worst_case_payout - 1.0).abs() < 1e-9);
+
+        assert!(solve_worlds(&markets, &[]).is_none());
+        assert!(feasible_worlds(MAX_LP_MARKETS + 1, &[]).is_empty());
+    }
+}
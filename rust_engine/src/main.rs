@@ -5,17 +5,44 @@ mod manifold_fetcher;
 mod telegram_notifier;
 mod cross_matcher;
 mod config;
+mod lp_solver;
+mod proposition;
+mod rules;
+mod strategy;
+mod resolution;
+mod allocator;
+mod consensus;
+mod ladder;
+mod calendar;
+mod market_filter;
+mod revalidate;
+mod latency;
+mod lifecycle;
+mod store;
+mod history;
+mod backtest;
+mod sweep;
 
-use engine::ArbitrageEngine;
+use engine::{ArbitrageEngine, CrossExecution, Timing};
+use allocator::PortfolioAllocator;
+use market_filter::{FilterStats, MarketFilter};
+use revalidate::{Rejection, Revalidator};
+use latency::LatencyStats;
+use lifecycle::{EventKind, OpportunityTracker};
+use store::AlertRecord;
+use history::PriceHistory;
+use strategy::StrategySettings;
 use polymarket_fetcher::PolymarketFetcher;
 use kalshi_fetcher::KalshiFetcher;
 use manifold_fetcher::ManifoldFetcher;
 use telegram_notifier::TelegramNotifier;
 use cross_matcher::CrossMatcher;
 use config::Config;
+use rules::RuleSet;
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 use std::env;
+use futures::future::join_all;
 use tokio::time::sleep;
 
 #[tokio::main]
@@ -23,6 +50,53 @@ async fn main() {
     dotenv::dotenv().ok();
     env_logger::init();
 
+    // Offline tooling subcommands
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("check-propositions") => {
+            let path = args.get(2).map(String::as_str).unwrap_or("propositions_corpus.json");
+            std::process::exit(if proposition::check_corpus(path) { 0 } else { 1 });
+        }
+        Some("validate-rules") => {
+            let path = args.get(2).map(String::as_str).unwrap_or("../rules.json");
+            std::process::exit(if validate_rules(path).await { 0 } else { 1 });
+        }
+        Some("backtest") => {
+            if let Err(e) = backtest::run(&args[2..]) {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+            std::process::exit(0);
+        }
+        Some("sweep") => {
+            if let Err(e) = sweep::run(&args[2..]) {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+            std::process::exit(0);
+        }
+        Some("history") => {
+            let Some(market_key) = args.get(2) else {
+                eprintln!("usage: history <market_key> [hours]");
+                std::process::exit(1);
+            };
+            let hours = args.get(3).and_then(|h| h.parse::<f64>().ok()).unwrap_or(24.0);
+            if let Err(e) = history::print_series(&Config::load().history, market_key, hours) {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+            std::process::exit(0);
+        }
+        Some(command @ ("mute" | "unmute" | "confirm-match" | "reject-match")) => {
+            if let Err(e) = store::run_command(command, &args[2..], &Config::load().storage) {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+            std::process::exit(0);
+        }
+        _ => {}
+    }
+
     // Load config from .env
     let bot_token = env::var("TELEGRAM_BOT_TOKEN").expect("TELEGRAM_BOT_TOKEN not set");
     let chat_id = env::var("TELEGRAM_CHAT_ID").expect("TELEGRAM_CHAT_ID not set");
@@ -30,16 +104,57 @@ async fn main() {
         .unwrap_or_else(|_| "1000".to_string())
         .parse::<f64>()
         .unwrap_or(1000.0);
+    let env_min_liquidity = env::var("MIN_LIQUIDITY").ok().and_then(|v| v.parse::<f64>().ok());
 
     let poly_fetcher = PolymarketFetcher::new();
     let kalshi_fetcher = KalshiFetcher::new();
     let manifold_fetcher = ManifoldFetcher::new();
     let notifier = TelegramNotifier::new(bot_token, chat_id);
-    let cross_matcher = CrossMatcher::new();
+    let mut cross_matcher = CrossMatcher::new(&Config::load().matching);
+    let revalidator = Revalidator {
+        polymarket: &poly_fetcher,
+        kalshi: &kalshi_fetcher,
+        manifold: &manifold_fetcher,
+    };
 
-    // Dedup: track already-alerted opportunity IDs (clear after 1 hour)
+    // Opportunities alert through the lifecycle tracker; signals (matches, divergences)
+    // alert once and are forgotten when they drop out of a scan
+    let startup_config = Config::load();
+    let mut tracker = OpportunityTracker::new(&startup_config.lifecycle);
     let mut sent_ids: HashSet<String> = HashSet::new();
-    let mut last_clear = Instant::now();
+
+    // Pick up alert state from before the restart so nothing re-alerts
+    let mut state = store::open(&startup_config.storage);
+    match state.prune(&startup_config.storage, chrono::Utc::now()) {
+        Ok(pruned) if pruned > 0 => println!("🗄️ Pruned {} old rows from the state store", pruned),
+        Ok(_) => {}
+        Err(e) => eprintln!("⚠️ State store prune failed: {}", e),
+    }
+    match state.load_lifecycle() {
+        Ok(records) => {
+            println!("🗄️ Restored {} open opportunities", records.len());
+            tracker.restore(records);
+        }
+        Err(e) => eprintln!("⚠️ Could not restore opportunities: {}", e),
+    }
+    match state.load_signals() {
+        Ok(ids) => sent_ids = ids,
+        Err(e) => eprintln!("⚠️ Could not restore alerted signals: {}", e),
+    }
+
+    // Price series for charts and backtests; compacted hourly
+    let mut price_history = if startup_config.history.enabled {
+        match PriceHistory::open(&startup_config.history.path) {
+            Ok(history) => Some(history),
+            Err(e) => {
+                eprintln!("⚠️ Price history {} unavailable ({}), not recording", startup_config.history.path, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let mut last_compact: Option<Instant> = None;
     
     println!("🚀 Rust HFT Arbitrage Engine Started!");
     println!("📡 Scanning Polymarket, Kalshi & Manifold");
@@ -52,13 +167,6 @@ async fn main() {
     }
 
     loop {
-        // Clear dedup cache every hour
-        if last_clear.elapsed() > Duration::from_secs(3600) {
-            sent_ids.clear();
-            last_clear = Instant::now();
-            println!("🔄 Cleared dedup cache");
-        }
-
         // Reload config each cycle to pick up changes from Telegram bot
         let config = Config::load();
         
@@ -69,12 +177,21 @@ async fn main() {
             continue;
         }
 
-        // Create engine with config settings
-        let engine = ArbitrageEngine::new(
-            config.min_roi_percent / 100.0,
-            config.min_profit_threshold,
-            total_capital
-        );
+        tracker.reconfigure(&config.lifecycle);
+        cross_matcher.reconfigure(&config.matching);
+
+        // Mutes and match verdicts can change from the CLI while we run
+        let mutes = state.active_mutes(chrono::Utc::now()).unwrap_or_else(|e| {
+            eprintln!("⚠️ Could not load mutes: {}", e);
+            Vec::new()
+        });
+        let verdicts = state.match_verdicts().unwrap_or_else(|e| {
+            eprintln!("⚠️ Could not load match verdicts: {}", e);
+            Default::default()
+        });
+
+        // Create engine with config settings (rules file is hot-reloaded the same way)
+        let engine = ArbitrageEngine::new(&config, total_capital, RuleSet::load());
 
         let start = Instant::now();
 
@@ -85,22 +202,58 @@ async fn main() {
             manifold_fetcher.fetch_all_markets()
         );
 
+        let snapshot = Instant::now();
         let mut all_markets = Vec::new();
         
         // Collect results
-        let poly_markets = match poly_result {
+        let mut poly_markets = match poly_result {
             Ok(m) => { println!("✓ Polymarket: {} markets", m.len()); m }
             Err(e) => { eprintln!("❌ Polymarket: {}", e); Vec::new() }
         };
-        let kalshi_markets = match kalshi_result {
+        let mut kalshi_markets = match kalshi_result {
             Ok(m) => { println!("✓ Kalshi: {} markets", m.len()); m }
             Err(e) => { eprintln!("❌ Kalshi: {}", e); Vec::new() }
         };
-        let manifold_markets = match manifold_result {
+        let mut manifold_markets = match manifold_result {
             Ok(m) => { println!("✓ Manifold: {} markets", m.len()); m }
             Err(e) => { eprintln!("❌ Manifold: {}", e); Vec::new() }
         };
 
+        // Book depth and last trade need a request per market, so only fetch them
+        // where a filter asks and the market's listing fields pass
+        let market_filter = MarketFilter::new(&config, env_min_liquidity);
+        let needs = |m: &engine::Market| market_filter.depth_needs(m);
+        tokio::join!(
+            poly_fetcher.fetch_depth(&mut poly_markets, needs),
+            kalshi_fetcher.fetch_depth(&mut kalshi_markets, needs)
+        );
+
+        // Record every fetched quote (filters are config, so history keeps the raw scan)
+        if let Some(history) = price_history.as_mut() {
+            let markets = poly_markets.iter().chain(&kalshi_markets).chain(&manifold_markets);
+            match history.record(markets, chrono::Utc::now()) {
+                Ok(written) => println!("📈 History: {} price changes recorded", written),
+                Err(e) => eprintln!("⚠️ Could not record price history: {}", e),
+            }
+            if last_compact.is_none_or(|t| t.elapsed() > Duration::from_secs(3600)) {
+                match history.compact(&config.history, chrono::Utc::now()) {
+                    Ok(deleted) if deleted > 0 => println!("📈 History: compacted {} old points", deleted),
+                    Ok(_) => {}
+                    Err(e) => eprintln!("⚠️ Could not compact price history: {}", e),
+                }
+                last_compact = Some(Instant::now());
+            }
+        }
+
+        // Drop thin and stale markets before they reach matching or any strategy
+        let mut filter_stats = FilterStats::default();
+        for markets in [&mut poly_markets, &mut kalshi_markets, &mut manifold_markets] {
+            market_filter.apply(markets, &mut filter_stats, chrono::Utc::now());
+        }
+        if filter_stats.total_dropped() > 0 {
+            println!("🧹 Filtered {} thin/stale markets ({})", filter_stats.total_dropped(), filter_stats.describe());
+        }
+
         all_markets.extend(poly_markets.iter().cloned());
         all_markets.extend(kalshi_markets.iter().cloned());
         all_markets.extend(manifold_markets.iter().cloned());
@@ -108,70 +261,283 @@ async fn main() {
         let fetch_duration = start.elapsed();
         println!("⚡ Fetch: {:.1}s ({} markets)", fetch_duration.as_secs_f64(), all_markets.len());
 
-        // 2. Single-platform arbitrage analysis
+        // 2. Cross-platform heuristic matching (every venue pair)
+        let cross_start = Instant::now();
+        let mut platform_markets: HashMap<String, Vec<&engine::Market>> = HashMap::new();
+        for m in &poly_markets {
+            platform_markets.entry("Polymarket".to_string()).or_default().push(m);
+        }
+        for m in &kalshi_markets {
+            platform_markets.entry("Kalshi".to_string()).or_default().push(m);
+        }
+        for m in &manifold_markets {
+            platform_markets.entry("Manifold".to_string()).or_default().push(m);
+        }
+
+        let mut cross_matches = cross_matcher.match_all(&platform_markets);
+        cross_matches.extend(cross_matcher.match_duplicates(&platform_markets));
+
+        // Human verdicts override the heuristics: rejected pairs are dropped, confirmed ones trusted
+        cross_matches.retain_mut(|cm| {
+            let (key_a, key_b) = cm.market_keys();
+            match verdicts.get(&key_a, &key_b) {
+                Some(false) => false,
+                Some(true) => {
+                    cm.confidence = 1.0;
+                    true
+                }
+                None => true,
+            }
+        });
+        println!("🔗 Cross-match: {}ms, {} matches", cross_start.elapsed().as_millis(), cross_matches.len());
+
+        // 3. Strategy analysis (the cross-platform strategy costs the matches above)
         let analysis_start = Instant::now();
-        let opportunities = engine.analyze_markets(&all_markets);
-        println!("🔍 Analysis: {}ms, {} opps", analysis_start.elapsed().as_millis(), opportunities.len());
+        let mut report = engine.analyze_markets(&all_markets, &mut cross_matches);
 
+        // Size the ranked list against venue cash and event caps
+        let allocation = PortfolioAllocator::new(&config, total_capital).allocate(&mut report.opportunities);
+        println!("💼 Allocated ${:.2} across {} opps", allocation.capital, allocation.funded);
 
+        println!("🔍 Analysis: {}ms, {} opps (dependencies: {}ms)",
+            analysis_start.elapsed().as_millis(), report.opportunities.len(), report.dependency_ms);
+        for stats in &report.strategies {
+            println!("   ├ {}: {} opps in {}ms", stats.kind.label(), stats.opportunities, stats.elapsed_ms);
+        }
+
+        // Lifecycle: only opened, materially changed and closed opportunities alert
+        let mut events = tracker.observe(&report.opportunities, chrono::Utc::now());
         let mut new_opps = 0;
-        for opp in &opportunities {
-            if sent_ids.contains(&opp.id) {
-                continue; // Already alerted
+        let mut faded = 0;
+        let mut alerted_timing: HashMap<String, Timing> = HashMap::new();
+        let muted: Vec<bool> = events.iter().map(|event| {
+            let opp = &event.record.opportunity;
+            let targets = std::iter::once(opp.id.as_str())
+                .chain(opp.legs.iter().flat_map(|l| [l.market_key.as_str(), l.event_key.as_str()]));
+            store::is_muted(&mutes, targets)
+        }).collect();
+
+        // Recheck against live prices, every event at once; unconfirmed opps are offered again next cycle
+        let (config_ref, revalidator_ref) = (&config, &revalidator);
+        let checks = join_all(events.iter_mut().zip(&muted).map(|(event, &muted)| async move {
+            if muted || event.kind == EventKind::Closed || !config_ref.revalidate_before_alert {
+                return None;
+            }
+            let opp = &mut event.record.opportunity;
+            let min_profit = StrategySettings::from_config(config_ref, opp.kind).min_profit;
+            Some(revalidator_ref.confirm(opp, snapshot, min_profit).await)
+        })).await;
+
+        for ((event, muted), check) in events.iter_mut().zip(muted).zip(checks) {
+            if muted {
+                continue;
+            }
+            let opp = &event.record.opportunity;
+            match check {
+                None => {}
+                Some(Ok(())) => event.record.current_roi = opp.roi_percent,
+                Some(Err(Rejection::Faded { net_profit })) => {
+                    println!("🫥 {} faded on refetch (net ${:.4} → ${:.4})", opp.id, opp.net_profit_after_fees, net_profit);
+                    faded += 1;
+                    continue;
+                }
+                Some(Err(Rejection::Unavailable { reason })) => {
+                    println!("⚠️ {} not confirmed: {}", opp.id, reason);
+                    faded += 1;
+                    continue;
+                }
             }
-            sent_ids.insert(opp.id.clone());
-            new_opps += 1;
 
+            let opp = &event.record.opportunity;
             println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("🎯 {} | ROI: {:.2}% | ${:.4}", opp.opp_type, opp.roi_percent, opp.net_profit_after_fees);
+            match event.kind {
+                EventKind::Closed => println!("🔚 {} closed after {}m | peak ROI {:.2}%",
+                    opp.id, (event.record.closed_at.unwrap_or_else(chrono::Utc::now) - event.record.first_seen).num_minutes(), event.record.peak_roi),
+                kind => println!("🎯 {} {} | ROI: {:.2}% | ${:.4} | {}", opp.kind.label(), kind.label(), opp.roi_percent, opp.net_profit_after_fees,
+                    match (opp.annualized_roi_percent, opp.days_to_resolution) {
+                        (Some(annualized), Some(days)) => format!("{:.1}% annualized over {:.0}d", annualized, days),
+                        (Some(annualized), None) => format!("{:.1}% annualized, resolution date unknown (assumed {:.0}d)", annualized, engine::UNDATED_HORIZON_DAYS),
+                        _ => "resolution date unknown".to_string(),
+                    }),
+            }
+            if let Some(c) = &opp.confirmation {
+                println!("✅ Confirmed {}ms after snapshot (refetch {}ms)", c.snapshot_to_confirm_ms, c.refetch_ms);
+            }
             println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
-            if let Err(e) = notifier.send_opportunity(opp).await {
+            let send_start = Instant::now();
+            let sent = notifier.send_event(event).await;
+            if let Err(e) = sent {
                 eprintln!("Failed to send alert: {}", e);
+                continue;
+            }
+            let alert = AlertRecord {
+                sent_at: chrono::Utc::now(),
+                kind: event.kind.label().to_string(),
+                target: event.record.opportunity.id.clone(),
+                roi_percent: Some(event.record.current_roi),
+                net_profit: Some(event.record.opportunity.net_profit_after_fees),
+                payload: serde_json::to_string(&event.record).unwrap_or_default(),
+            };
+            if let Err(e) = state.record_alert(&alert) {
+                eprintln!("⚠️ Could not record alert: {}", e);
+            }
+            if event.kind == EventKind::Closed {
+                continue;
             }
-        }
 
-        // 3. Cross-platform heuristic matching
-        let cross_start = Instant::now();
-        let mut platform_markets: HashMap<String, Vec<&engine::Market>> = HashMap::new();
-        for m in &poly_markets {
-            platform_markets.entry("Polymarket".to_string()).or_default().push(m);
+            let opp = &mut event.record.opportunity;
+            opp.timing.send_ms = Some(send_start.elapsed().as_millis());
+            opp.timing.end_to_end_ms = opp.oldest_data().map(|t| (chrono::Utc::now() - t).num_milliseconds());
+            alerted_timing.insert(opp.id.clone(), opp.timing.clone());
+            tracker.mark_alerted(&opp.id, opp.roi_percent);
+            new_opps += 1;
         }
-        for m in &kalshi_markets {
-            platform_markets.entry("Kalshi".to_string()).or_default().push(m);
+
+        let mut latency = LatencyStats::default();
+        for opp in &report.opportunities {
+            latency.record(alerted_timing.get(&opp.id).unwrap_or(&opp.timing));
         }
-        for m in &manifold_markets {
-            platform_markets.entry("Manifold".to_string()).or_default().push(m);
+        if !latency.is_empty() {
+            println!("⏱️ Latency p50/p95: {}", latency.describe());
         }
 
-        let cross_matches = cross_matcher.match_all(&platform_markets);
-        println!("🔗 Cross-match: {}ms, {} matches", cross_start.elapsed().as_millis(), cross_matches.len());
-
         let mut new_cross = 0;
-        for cm in &cross_matches {
-            let cm_id = format!("cross_{}_{}", cm.id_a, cm.id_b);
-            if sent_ids.contains(&cm_id) {
-                continue; // Already alerted
+        let mut live_signals: HashSet<String> = HashSet::new();
+        let pending: Vec<bool> = cross_matches.iter().map(|cm| {
+            let cm_id = format!("match_{}_{}", cm.id_a, cm.id_b);
+            live_signals.insert(cm_id.clone());
+            let (key_a, key_b) = cm.market_keys();
+            !sent_ids.contains(&cm_id) && !store::is_muted(&mutes, [cm_id.as_str(), &key_a, &key_b].into_iter())
+        }).collect();
+
+        // An arb is only sent if it still holds at live prices; all are rechecked at once,
+        // and those that fail are offered again next cycle
+        let checks = join_all(cross_matches.iter_mut().zip(&pending).map(|(cm, &pending)| async move {
+            match (cm.execution.as_mut(), pending && config_ref.revalidate_before_alert) {
+                (Some(CrossExecution::Arb(opp)), true) => {
+                    let min_profit = StrategySettings::from_config(config_ref, opp.kind).min_profit;
+                    Some(revalidator_ref.confirm(opp, snapshot, min_profit).await)
+                }
+                _ => None,
             }
-            sent_ids.insert(cm_id);
+        })).await;
+
+        for ((cm, pending), check) in cross_matches.iter().zip(pending).zip(checks) {
+            if !pending {
+                continue; // Already alerted or muted
+            }
+            let cm_id = format!("match_{}_{}", cm.id_a, cm.id_b);
+            if let (Some(CrossExecution::Arb(opp)), Some(Err(rejection))) = (&cm.execution, check) {
+                match rejection {
+                    Rejection::Faded { net_profit } =>
+                        println!("🫥 {} arb faded on refetch (net ${:.4} → ${:.4})", cm_id, opp.net_profit_after_fees, net_profit),
+                    Rejection::Unavailable { reason } => println!("⚠️ {} arb not confirmed: {}", cm_id, reason),
+                }
+                faded += 1;
+                continue;
+            }
+            sent_ids.insert(cm_id.clone());
             new_cross += 1;
 
-            println!("🔗 [{}] {} ↔ {} | diff: {:.1}% | conf: {:.0}%",
-                cm.category, cm.platform_a, cm.platform_b, 
-                cm.price_diff * 100.0, cm.confidence * 100.0);
+            println!("🔗 [{}] {} ↔ {} | diff: {:.1}% | conf: {:.0}% | {}",
+                cm.category, cm.platform_a, cm.platform_b,
+                cm.price_diff * 100.0, cm.confidence * 100.0,
+                match &cm.execution {
+                    Some(CrossExecution::Arb(opp)) => format!("arb ${:.4} net", opp.net_profit_after_fees),
+                    Some(CrossExecution::NoArbAfterFees { net_profit, .. }) => format!("no arb after fees (${:.4})", net_profit),
+                    Some(CrossExecution::NotExecutable { reason }) => format!("signal only: {}", reason),
+                    None => "unpriced".to_string(),
+                });
 
-            if let Err(e) = notifier.send_cross_match(cm).await {
-                eprintln!("Failed to send cross-match: {}", e);
+            match notifier.send_cross_match(cm).await {
+                Ok(()) => {
+                    let alert = AlertRecord {
+                        sent_at: chrono::Utc::now(),
+                        kind: "cross_match".to_string(),
+                        target: cm_id,
+                        roi_percent: None,
+                        net_profit: match &cm.execution {
+                            Some(CrossExecution::Arb(opp)) => Some(opp.net_profit_after_fees),
+                            Some(CrossExecution::NoArbAfterFees { net_profit, .. }) => Some(*net_profit),
+                            _ => None,
+                        },
+                        payload: serde_json::to_string(cm).unwrap_or_default(),
+                    };
+                    if let Err(e) = state.record_alert(&alert) {
+                        eprintln!("⚠️ Could not record alert: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Failed to send cross-match: {}", e),
             }
         }
 
+        // 4. Consensus divergence signal over matched clusters
+        let mut new_div = 0;
+        if config.divergence.enabled {
+            let divergences = consensus::find_divergences(&cross_matches, &all_markets, &config.divergence);
+            println!("📐 Divergence: {} clusters off consensus", divergences.len());
+
+            for d in &divergences {
+                live_signals.insert(d.id.clone());
+                if sent_ids.contains(&d.id) {
+                    continue; // Already alerted
+                }
+                let market_keys: Vec<String> = d.venues.iter().map(|v| format!("{}:{}", v.platform, v.market_id)).collect();
+                if store::is_muted(&mutes, std::iter::once(d.id.as_str()).chain(market_keys.iter().map(String::as_str))) {
+                    continue;
+                }
+                sent_ids.insert(d.id.clone());
+                new_div += 1;
+
+                for v in d.flagged() {
+                    println!("📐 [{}] {} ${:.3} vs consensus ${:.3} ({:+.1}pp, z {:+.1})",
+                        d.category, v.platform, v.price, d.consensus, v.gap * 100.0, v.z_score);
+                }
+
+                match notifier.send_divergence(d).await {
+                    Ok(()) => {
+                        let alert = AlertRecord {
+                            sent_at: chrono::Utc::now(),
+                            kind: "divergence".to_string(),
+                            target: d.id.clone(),
+                            roi_percent: None,
+                            net_profit: None,
+                            payload: serde_json::to_string(d).unwrap_or_default(),
+                        };
+                        if let Err(e) = state.record_alert(&alert) {
+                            eprintln!("⚠️ Could not record alert: {}", e);
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to send divergence: {}", e),
+                }
+            }
+        }
+
+        // Forget signals that dropped out of this scan so they alert again if they return
+        sent_ids.retain(|id| live_signals.contains(id));
+
+        // Persist alert state so a restart picks up where this cycle left off
+        let closed: Vec<_> = events.iter().filter(|e| e.kind == EventKind::Closed).map(|e| &e.record).collect();
+        let persisted: Vec<_> = tracker.alerted().chain(closed).collect();
+        if let Err(e) = state.save_lifecycle(&persisted) {
+            eprintln!("⚠️ Could not save opportunities: {}", e);
+        }
+        if let Err(e) = state.save_signals(&sent_ids) {
+            eprintln!("⚠️ Could not save alerted signals: {}", e);
+        }
+        if let Err(e) = state.prune(&config.storage, chrono::Utc::now()) {
+            eprintln!("⚠️ State store prune failed: {}", e);
+        }
+
         // Summary
         let scan_time = start.elapsed().as_millis() as u64;
-        println!("📊 New alerts: {} opps + {} cross (dedup cache: {})",
-            new_opps, new_cross, sent_ids.len());
+        println!("📊 New alerts: {} opps + {} cross + {} divergence, {} faded on revalidation ({} opps open, {} signals tracked)",
+            new_opps, new_cross, new_div, faded, tracker.open_count(), sent_ids.len());
 
-        if new_opps + new_cross > 0 {
-            if let Err(e) = notifier.send_summary(all_markets.len(), new_opps + new_cross, scan_time).await {
+        let new_alerts = new_opps + new_cross + new_div;
+        if new_alerts > 0 {
+            if let Err(e) = notifier.send_summary(all_markets.len(), new_alerts, scan_time, &report.strategies, &filter_stats, &latency).await {
                 eprintln!("Failed to send summary: {}", e);
             }
         }
@@ -180,3 +546,37 @@ async fn main() {
         sleep(Duration::from_secs(config.scan_interval_seconds)).await;
     }
 }
+
+/// Fetch live markets and report rules that match none of them
+async fn validate_rules(path: &str) -> bool {
+    let rules = match std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| RuleSet::from_json(&content))
+    {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("❌ Invalid rules file {}: {}", path, e);
+            return false;
+        }
+    };
+
+    let poly_fetcher = PolymarketFetcher::new();
+    let kalshi_fetcher = KalshiFetcher::new();
+    let manifold_fetcher = ManifoldFetcher::new();
+    let (poly_result, kalshi_result, manifold_result) = tokio::join!(
+        poly_fetcher.fetch_all_markets(),
+        kalshi_fetcher.fetch_all_markets(),
+        manifold_fetcher.fetch_all_markets()
+    );
+
+    let mut texts = Vec::new();
+    for (name, result) in [("Polymarket", poly_result), ("Kalshi", kalshi_result), ("Manifold", manifold_result)] {
+        match result {
+            Ok(markets) => texts.extend(markets.iter().map(|m| m.full_text().to_lowercase())),
+            Err(e) => eprintln!("❌ {}: {}", name, e),
+        }
+    }
+
+    println!("🔍 Validating {} rules against {} markets", rules.rules.len(), texts.len());
+    rules.report_unmatched(&texts) == 0
+}
@@ -1,7 +1,8 @@
 use serde::Deserialize;
 use reqwest::Client;
 use std::error::Error;
-use chrono::{Utc, TimeZone};
+use chrono::{DateTime, TimeZone, Utc};
+use crate::engine::Currency;
 
 #[derive(Debug, Deserialize, Default)]
 struct ManifoldMarket {
@@ -15,10 +16,19 @@ struct ManifoldMarket {
     url: Option<String>,
     #[serde(default)]
     volume: Option<f64>,
+    #[serde(rename = "totalLiquidity", default)]
+    total_liquidity: Option<f64>,
+    #[serde(rename = "lastBetTime", default)]
+    last_bet_time: Option<i64>,
+    #[serde(rename = "lastUpdatedTime", default)]
+    last_updated_time: Option<i64>,
     #[serde(rename = "isResolved", default)]
     is_resolved: bool,
     #[serde(rename = "closeTime", default)]
     close_time: Option<i64>,
+    /// "MANA" (play money) or "CASH" (sweepcash)
+    #[serde(default)]
+    token: Option<String>,
 }
 
 pub struct ManifoldFetcher {
@@ -53,6 +63,7 @@ impl ManifoldFetcher {
         }
 
         let markets: Vec<ManifoldMarket> = response.json().await?;
+        let received = Utc::now();
         println!("[Manifold] Received {} markets", markets.len());
 
         for m in markets {
@@ -68,28 +79,65 @@ impl ManifoldFetcher {
                 }
             }
 
-            let prob = m.probability.unwrap_or(0.0);
-            let close_date = m.close_time.map(|ct| {
-                Utc.timestamp_millis_opt(ct)
-                    .single()
-                    .map(|dt| dt.to_rfc3339())
-                    .unwrap_or_default()
-            });
-
-            all_markets.push(crate::engine::Market {
-                id: m.id,
-                question: Some(m.question),
-                title: None,
-                subtitle: None,
-                outcome_prices: vec![prob, 1.0 - prob],
-                platform: "Manifold".to_string(),
-                liquidity: m.volume.unwrap_or(0.0),
-                close_date,
-                url: m.url,
-            });
+            all_markets.push(to_market(m, received));
         }
 
-        println!("[Manifold] Total: {} active markets", all_markets.len());
+        let cash = all_markets.iter().filter(|m| m.currency == Currency::Sweepcash).count();
+        println!("[Manifold] Total: {} active markets ({} sweepcash, rest mana/signal only)", all_markets.len(), cash);
         Ok(all_markets)
     }
+
+    /// Refetch a single market by id (pre-alert revalidation)
+    pub async fn fetch_market(&self, id: &str) -> Result<crate::engine::Market, Box<dyn Error>> {
+        let url = format!("https://api.manifold.markets/v0/market/{}", id);
+        let market: ManifoldMarket = self.client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(to_market(market, Utc::now()))
+    }
+}
+
+/// Convert an API market into the engine's market type
+fn to_market(m: ManifoldMarket, received: DateTime<Utc>) -> crate::engine::Market {
+    let prob = m.probability.unwrap_or(0.0);
+    let close_date = m.close_time.map(|ct| {
+        Utc.timestamp_millis_opt(ct)
+            .single()
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default()
+    });
+
+    crate::engine::Market {
+        id: m.id,
+        question: Some(m.question),
+        title: None,
+        subtitle: None,
+        outcome_prices: vec![prob, 1.0 - prob],
+        outcomes: vec!["Yes".to_string(), "No".to_string()],
+        platform: "Manifold".to_string(),
+        liquidity: m.total_liquidity.or(m.volume).unwrap_or(0.0),
+        close_date,
+        url: m.url,
+        rules: None,
+        resolution_source: None,
+        event_id: None,
+        currency: match m.token.as_deref() {
+            Some("CASH") => Currency::Sweepcash,
+            _ => Currency::Mana,
+        },
+        volume: m.volume,
+        // AMM: always quoted, no spread or book depth to speak of
+        spread: None,
+        best_bid: None,
+        best_ask: None,
+        last_trade_time: m.last_bet_time.and_then(|t| Utc.timestamp_millis_opt(t).single()),
+        top_of_book_size: None,
+        fetched_at: Some(received),
+        venue_time: m.last_updated_time.and_then(|t| Utc.timestamp_millis_opt(t).single()),
+    }
 }
@@ -0,0 +1,192 @@
+use crate::config::{Config, MarketFilterConfig};
+use crate::engine::Market;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+/// Per-filter drop counts for one scan
+#[derive(Debug, Clone, Default)]
+pub struct FilterStats {
+    pub kept: usize,
+    pub dropped: BTreeMap<&'static str, usize>,
+}
+
+impl FilterStats {
+    pub fn total_dropped(&self) -> usize {
+        self.dropped.values().sum()
+    }
+
+    /// "liquidity 120, spread 31" (empty when nothing was dropped)
+    pub fn describe(&self) -> String {
+        self.dropped.iter()
+            .map(|(name, count)| format!("{} {}", name, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Which per-market order book data a filter still needs from the venue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DepthNeeds {
+    pub book: bool,
+    pub last_trade: bool,
+}
+
+impl DepthNeeds {
+    pub fn any(&self) -> bool {
+        self.book || self.last_trade
+    }
+}
+
+/// Drops thin and stale markets before matching and analysis.
+///
+/// Limits come from the "default" section of `market_filters`, overridden
+/// field by field by the venue's own section. `MIN_LIQUIDITY` from the
+/// environment is the fallback liquidity floor.
+pub struct MarketFilter {
+    default: MarketFilterConfig,
+    venues: HashMap<String, MarketFilterConfig>,
+}
+
+impl MarketFilter {
+    pub fn new(config: &Config, env_min_liquidity: Option<f64>) -> Self {
+        let mut venues = config.market_filters.clone();
+        let mut default = venues.remove("default").unwrap_or_default();
+        default.min_liquidity = default.min_liquidity.or(env_min_liquidity);
+
+        Self { default, venues }
+    }
+
+    fn limits(&self, platform: &str) -> MarketFilterConfig {
+        let Some(venue) = self.venues.get(platform) else {
+            return self.default.clone();
+        };
+        MarketFilterConfig {
+            min_liquidity: venue.min_liquidity.or(self.default.min_liquidity),
+            min_volume: venue.min_volume.or(self.default.min_volume),
+            max_spread: venue.max_spread.or(self.default.max_spread),
+            max_last_trade_age_hours: venue.max_last_trade_age_hours.or(self.default.max_last_trade_age_hours),
+            min_top_of_book_size: venue.min_top_of_book_size.or(self.default.min_top_of_book_size),
+        }
+    }
+
+    /// Book depth and last trade cost a request per market, so they are only
+    /// fetched for markets that pass the filters on the listing's own fields
+    pub fn depth_needs(&self, market: &Market) -> DepthNeeds {
+        let limits = self.limits(&market.platform);
+        if Self::quote_rejection(&limits, market).is_some() {
+            return DepthNeeds::default();
+        }
+        DepthNeeds {
+            book: limits.min_top_of_book_size.is_some() && market.top_of_book_size.is_none(),
+            last_trade: limits.max_last_trade_age_hours.is_some() && market.last_trade_time.is_none(),
+        }
+    }
+
+    /// First filter the market fails, if any
+    fn rejection(&self, market: &Market, now: DateTime<Utc>) -> Option<&'static str> {
+        let limits = self.limits(&market.platform);
+        Self::quote_rejection(&limits, market).or_else(|| Self::depth_rejection(&limits, market, now))
+    }
+
+    /// Filters on fields every venue listing carries
+    fn quote_rejection(limits: &MarketFilterConfig, market: &Market) -> Option<&'static str> {
+        if limits.min_liquidity.is_some_and(|min| market.liquidity < min) {
+            return Some("liquidity");
+        }
+        if let (Some(min), Some(volume)) = (limits.min_volume, market.volume) {
+            if volume < min {
+                return Some("volume");
+            }
+        }
+        if let (Some(max), Some(spread)) = (limits.max_spread, market.spread) {
+            if spread > max {
+                return Some("spread");
+            }
+        }
+        None
+    }
+
+    /// Filters on order book data fetched per market
+    fn depth_rejection(limits: &MarketFilterConfig, market: &Market, now: DateTime<Utc>) -> Option<&'static str> {
+        if let (Some(max_hours), Some(last_trade)) = (limits.max_last_trade_age_hours, market.last_trade_time) {
+            if (now - last_trade).num_seconds() as f64 / 3600.0 > max_hours {
+                return Some("stale");
+            }
+        }
+        if let (Some(min), Some(size)) = (limits.min_top_of_book_size, market.top_of_book_size) {
+            if size < min {
+                return Some("book depth");
+            }
+        }
+        None
+    }
+
+    /// Remove filtered markets in place and count them; staleness is judged as of `now`
+    pub fn apply(&self, markets: &mut Vec<Market>, stats: &mut FilterStats, now: DateTime<Utc>) {
+        markets.retain(|m| match self.rejection(m, now) {
+            Some(filter) => {
+                *stats.dropped.entry(filter).or_insert(0) += 1;
+                false
+            }
+            None => {
+                stats.kept += 1;
+                true
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn filter() -> MarketFilter {
+        let config: Config = serde_json::from_str(r#"{"market_filters": {
+            "default": { "max_spread": 0.1, "max_last_trade_age_hours": 24, "min_top_of_book_size": 5 },
+            "Manifold": { "min_liquidity": 100, "max_last_trade_age_hours": 72 }
+        }}"#).unwrap();
+        MarketFilter::new(&config, Some(50.0))
+    }
+
+    fn market(platform: &str, id: &str) -> Market {
+        Market::test(platform, id, "Will it rain?", &[0.5, 0.5])
+    }
+
+    #[test]
+    fn counts_each_market_under_its_first_failed_filter() {
+        let now = Utc::now();
+        let mut markets = vec![
+            Market { top_of_book_size: Some(10.0), last_trade_time: Some(now - Duration::hours(1)), ..market("Kalshi", "kept") },
+            // Depth data not fetched yet: nothing to judge it on
+            market("Polymarket", "unknown_depth"),
+            // Env floor of 50 applies to venues without their own
+            Market { liquidity: 40.0, ..market("Kalshi", "thin") },
+            Market { liquidity: 40.0, spread: Some(0.5), ..market("Polymarket", "thin_and_wide") },
+            Market { spread: Some(0.2), ..market("Kalshi", "wide") },
+            Market { last_trade_time: Some(now - Duration::hours(30)), ..market("Kalshi", "stale") },
+            // Manifold allows 72 hours but needs 100 liquidity
+            Market { last_trade_time: Some(now - Duration::hours(30)), ..market("Manifold", "recent_enough") },
+            Market { liquidity: 80.0, ..market("Manifold", "thin_manifold") },
+            Market { top_of_book_size: Some(2.0), ..market("Polymarket", "shallow") },
+        ];
+        let mut stats = FilterStats::default();
+        filter().apply(&mut markets, &mut stats, now);
+
+        let kept: Vec<&str> = markets.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(kept, vec!["kept", "unknown_depth", "recent_enough"]);
+        assert_eq!(stats.kept, 3);
+        assert_eq!(stats.total_dropped(), 6);
+        assert_eq!(stats.describe(), "book depth 1, liquidity 3, spread 1, stale 1");
+    }
+
+    #[test]
+    fn depth_is_only_fetched_for_markets_passing_quote_filters() {
+        let filter = filter();
+        assert_eq!(filter.depth_needs(&market("Kalshi", "a")), DepthNeeds { book: true, last_trade: true });
+        assert!(!filter.depth_needs(&Market { spread: Some(0.3), ..market("Kalshi", "wide") }).any());
+
+        let with_book = Market { top_of_book_size: Some(10.0), ..market("Polymarket", "b") };
+        assert_eq!(filter.depth_needs(&with_book), DepthNeeds { book: false, last_trade: true });
+    }
+}
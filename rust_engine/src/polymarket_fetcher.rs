@@ -1,8 +1,15 @@
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use reqwest::Client;
+use std::collections::HashMap;
 use std::error::Error;
+use std::sync::Mutex;
 use crate::config::Config;
-use chrono::Utc;
+use crate::market_filter::DepthNeeds;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::stream::{self, StreamExt};
+
+/// Book/trades requests in flight at once
+const DEPTH_CONCURRENCY: usize = 8;
 
 #[derive(Debug, Deserialize, Default)]
 struct PolymarketMarket {
@@ -15,7 +22,21 @@ struct PolymarketMarket {
     #[serde(rename = "outcomePrices", default)]
     outcome_prices: Option<String>,
     #[serde(default)]
+    outcomes: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(rename = "resolutionSource", default)]
+    resolution_source: Option<String>,
+    #[serde(default)]
     liquidity: Option<String>,
+    #[serde(rename = "volumeNum", default)]
+    volume: Option<f64>,
+    #[serde(rename = "bestBid", default)]
+    best_bid: Option<f64>,
+    #[serde(rename = "bestAsk", default)]
+    best_ask: Option<f64>,
+    #[serde(default)]
+    spread: Option<f64>,
     #[serde(default)]
     closed: bool,
     #[serde(default)]
@@ -26,6 +47,45 @@ struct PolymarketMarket {
     end_date: Option<String>,
     #[serde(default)]
     events: Vec<PolymarketEvent>,
+    /// JSON array of CLOB token ids, parallel to `outcomes`
+    #[serde(rename = "clobTokenIds", default)]
+    clob_token_ids: Option<String>,
+    #[serde(rename = "conditionId", default)]
+    condition_id: Option<String>,
+}
+
+/// CLOB order book for one outcome token
+#[derive(Debug, Deserialize, Default)]
+struct ClobBook {
+    #[serde(default)]
+    bids: Vec<ClobLevel>,
+    #[serde(default)]
+    asks: Vec<ClobLevel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClobLevel {
+    price: String,
+    size: String,
+}
+
+impl ClobBook {
+    /// Shares at the best YES ask and best NO ask, whichever is smaller.
+    /// The books are mirrored, so a NO ask is a YES bid.
+    fn top_of_book_size(&self) -> f64 {
+        let best = |levels: &[ClobLevel], highest: bool| levels.iter()
+            .filter_map(|l| Some((l.price.parse::<f64>().ok()?, l.size.parse::<f64>().ok()?)))
+            .max_by(|a, b| if highest { a.0.total_cmp(&b.0) } else { b.0.total_cmp(&a.0) })
+            .map(|(_, size)| size)
+            .unwrap_or(0.0);
+        best(&self.asks, false).min(best(&self.bids, true))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DataApiTrade {
+    /// Unix seconds
+    timestamp: i64,
 }
 
 #[derive(Debug, Deserialize, Default, Clone)]
@@ -37,6 +97,10 @@ struct PolymarketEvent {
 pub struct PolymarketFetcher {
     client: Client,
     base_url: String,
+    clob_url: String,
+    data_url: String,
+    /// Gamma market id → (YES token id, condition id) from the last listing
+    book_ids: Mutex<HashMap<String, (String, String)>>,
 }
 
 impl PolymarketFetcher {
@@ -49,6 +113,9 @@ impl PolymarketFetcher {
         Self {
             client,
             base_url: "https://gamma-api.polymarket.com".to_string(),
+            clob_url: "https://clob.polymarket.com".to_string(),
+            data_url: "https://data-api.polymarket.com".to_string(),
+            book_ids: Mutex::new(HashMap::new()),
         }
     }
 
@@ -60,6 +127,7 @@ impl PolymarketFetcher {
         let filter_enabled = !config.enabled_categories.is_empty();
 
         println!("[Polymarket] Starting fetch (max {} pages)...", max_pages);
+        self.book_ids.lock().unwrap().clear();
         let mut all_markets = Vec::new();
         let mut offset = 0;
         let mut page_count = 0;
@@ -82,6 +150,7 @@ impl PolymarketFetcher {
                 .json()
                 .await?;
 
+            let received = Utc::now();
             println!("[Polymarket] Received {} markets", markets.len());
 
             if markets.is_empty() {
@@ -97,7 +166,7 @@ impl PolymarketFetcher {
                 }
 
                 // Parse and check close date
-                let close_date = market.end_date_iso.or(market.end_date);
+                let close_date = market.end_date_iso.clone().or(market.end_date.clone());
                 if let Some(ref cd) = close_date {
                     if let Ok(dt) = cd.parse::<chrono::DateTime<Utc>>() {
                         if dt < Utc::now() {
@@ -117,40 +186,13 @@ impl PolymarketFetcher {
                     }
                 }
 
-                let prices: Vec<f64> = if let Some(ref prices_str) = market.outcome_prices {
-                    serde_json::from_str(prices_str).unwrap_or_default()
-                } else {
-                    Vec::new()
-                };
-
-                let liquidity = market.liquidity
-                    .and_then(|l| l.parse::<f64>().ok())
-                    .unwrap_or(0.0);
-
-                // Build correct Polymarket URL
-                let url = {
-                    let event_slug = market.events.first()
-                        .and_then(|e| e.slug.as_deref());
-                    let market_slug = market.slug.as_deref();
-
-                    match (event_slug, market_slug) {
-                        (Some(es), Some(ms)) => Some(format!("https://polymarket.com/event/{}/{}", es, ms)),
-                        (Some(es), None) => Some(format!("https://polymarket.com/event/{}", es)),
-                        _ => None,
+                if let (Some(tokens), Some(condition)) = (&market.clob_token_ids, &market.condition_id) {
+                    let tokens: Vec<String> = serde_json::from_str(tokens).unwrap_or_default();
+                    if let Some(yes_token) = tokens.into_iter().next() {
+                        self.book_ids.lock().unwrap().insert(market.id.clone(), (yes_token, condition.clone()));
                     }
-                };
-
-                all_markets.push(crate::engine::Market {
-                    id: market.id.clone(),
-                    question: Some(market.question),
-                    title: None,
-                    subtitle: None,
-                    outcome_prices: prices,
-                    platform: "Polymarket".to_string(),
-                    liquidity,
-                    close_date,
-                    url,
-                });
+                }
+                all_markets.push(to_market(market, close_date, received));
             }
 
             offset += LIMIT;
@@ -160,4 +202,156 @@ impl PolymarketFetcher {
         println!("[Polymarket] Total: {} markets fetched (filtered)", all_markets.len());
         Ok(all_markets)
     }
+
+    /// Fill in top-of-book size and last trade time where the filters need them,
+    /// one CLOB book and one trades request per market. Failures leave the field unset.
+    pub async fn fetch_depth(&self, markets: &mut [crate::engine::Market], needs: impl Fn(&crate::engine::Market) -> DepthNeeds) {
+        let wanted: Vec<(&mut crate::engine::Market, DepthNeeds, (String, String))> = {
+            let book_ids = self.book_ids.lock().unwrap();
+            markets.iter_mut()
+                .filter_map(|m| {
+                    let n = needs(m);
+                    let ids = book_ids.get(&m.id).cloned()?;
+                    n.any().then_some((m, n, ids))
+                })
+                .collect()
+        };
+        if wanted.is_empty() {
+            return;
+        }
+        let requested = wanted.len();
+
+        let failures: usize = stream::iter(wanted)
+            .map(|(market, needs, (token, condition))| async move {
+                let mut failed = 0;
+                if needs.book {
+                    match self.fetch_book(&token).await {
+                        Ok(book) => market.top_of_book_size = Some(book.top_of_book_size()),
+                        Err(_) => failed = 1,
+                    }
+                }
+                if needs.last_trade {
+                    match self.fetch_last_trade(&condition).await {
+                        Ok(last) => market.last_trade_time = last,
+                        Err(_) => failed = 1,
+                    }
+                }
+                failed
+            })
+            .buffer_unordered(DEPTH_CONCURRENCY)
+            .fold(0, |total, failed| async move { total + failed })
+            .await;
+
+        println!("[Polymarket] Book depth for {} markets ({} failed)", requested, failures);
+    }
+
+    async fn fetch_book(&self, token_id: &str) -> Result<ClobBook, Box<dyn Error>> {
+        let url = format!("{}/book?token_id={}", self.clob_url, token_id);
+        Ok(self.client.get(&url).send().await?.error_for_status()?.json().await?)
+    }
+
+    /// Time of the latest trade; None if the market never traded
+    async fn fetch_last_trade(&self, condition_id: &str) -> Result<Option<DateTime<Utc>>, Box<dyn Error>> {
+        let url = format!("{}/trades?market={}&limit=1", self.data_url, condition_id);
+        let trades: Vec<DataApiTrade> = self.client.get(&url).send().await?.error_for_status()?.json().await?;
+        Ok(trades.first().and_then(|t| Utc.timestamp_opt(t.timestamp, 0).single()))
+    }
+
+    /// Refetch a single market by id (pre-alert revalidation)
+    pub async fn fetch_market(&self, id: &str) -> Result<crate::engine::Market, Box<dyn Error>> {
+        let url = format!("{}/markets/{}", self.base_url, id);
+        let market: PolymarketMarket = self.client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let close_date = market.end_date_iso.clone().or(market.end_date.clone());
+        Ok(to_market(market, close_date, Utc::now()))
+    }
+}
+
+/// Convert a Gamma API market into the engine's market type
+fn to_market(market: PolymarketMarket, close_date: Option<String>, received: DateTime<Utc>) -> crate::engine::Market {
+    let prices: Vec<f64> = if let Some(ref prices_str) = market.outcome_prices {
+        serde_json::from_str(prices_str).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    let outcomes: Vec<String> = market.outcomes.as_deref()
+        .and_then(|o| serde_json::from_str(o).ok())
+        .unwrap_or_default();
+
+    let liquidity = market.liquidity
+        .and_then(|l| l.parse::<f64>().ok())
+        .unwrap_or(0.0);
+
+    let spread = market.spread.or(match (market.best_bid, market.best_ask) {
+        (Some(bid), Some(ask)) => Some(ask - bid),
+        _ => None,
+    });
+    // No YES bid means no NO ask (and vice versa)
+    let top_of_book_size = match (market.best_bid, market.best_ask) {
+        (Some(bid), Some(ask)) if bid <= 0.0 || ask >= 1.0 => Some(0.0),
+        _ => None,
+    };
+
+    // Build correct Polymarket URL
+    let url = {
+        let event_slug = market.events.first()
+            .and_then(|e| e.slug.as_deref());
+        let market_slug = market.slug.as_deref();
+
+        match (event_slug, market_slug) {
+            (Some(es), Some(ms)) => Some(format!("https://polymarket.com/event/{}/{}", es, ms)),
+            (Some(es), None) => Some(format!("https://polymarket.com/event/{}", es)),
+            _ => None,
+        }
+    };
+
+    crate::engine::Market {
+        id: market.id.clone(),
+        question: Some(market.question),
+        title: None,
+        subtitle: None,
+        outcome_prices: prices,
+        platform: "Polymarket".to_string(),
+        liquidity,
+        close_date,
+        url,
+        outcomes,
+        rules: market.description,
+        resolution_source: market.resolution_source.filter(|s| !s.is_empty()),
+        event_id: market.events.first().and_then(|e| e.slug.clone()),
+        currency: crate::engine::Currency::Usdc,
+        volume: market.volume,
+        spread,
+        best_bid: market.best_bid,
+        best_ask: market.best_ask,
+        last_trade_time: None,
+        top_of_book_size,
+        fetched_at: Some(received),
+        // Gamma's updatedAt is the last metadata edit, not a quote time
+        venue_time: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_of_book_is_the_thinner_side() {
+        let book: ClobBook = serde_json::from_str(r#"{
+            "bids": [{"price": "0.40", "size": "900"}, {"price": "0.45", "size": "120"}],
+            "asks": [{"price": "0.55", "size": "15"}, {"price": "0.50", "size": "60"}]
+        }"#).unwrap();
+        assert_eq!(book.top_of_book_size(), 60.0);
+
+        let empty: ClobBook = serde_json::from_str(r#"{"bids": [], "asks": [{"price": "0.5", "size": "10"}]}"#).unwrap();
+        assert_eq!(empty.top_of_book_size(), 0.0);
+    }
 }
@@ -0,0 +1,539 @@
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparator {
+    Above,
+    Below,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdUnit {
+    Dollar,
+    Percent,
+    Plain,
+}
+
+impl ThresholdUnit {
+    /// "$100k" and "100k" describe the same quantity; percentages never mix with amounts
+    fn compatible(self, other: ThresholdUnit) -> bool {
+        (self == ThresholdUnit::Percent) == (other == ThresholdUnit::Percent)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadlineKind {
+    /// Event happens at any point up to the date ("by June 30", "in 2025")
+    By,
+    /// Condition is checked on the date itself ("on June 30", "at the end of 2025")
+    On,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Threshold {
+    pub value: f64,
+    pub unit: ThresholdUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deadline {
+    pub date: NaiveDate,
+    pub kind: DeadlineKind,
+}
+
+/// Structured form of a market question:
+/// "Will BTC be above $120k by June 30, 2025?" -> bitcoin / above / 120000 $ / by 2025-06-30
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Proposition {
+    pub subject: String,
+    pub comparator: Option<Comparator>,
+    pub threshold: Option<Threshold>,
+    pub deadline: Option<Deadline>,
+}
+
+impl Proposition {
+    /// True when `self` resolving YES forces `other` to resolve YES,
+    /// and the two are not the same proposition
+    pub fn implies(&self, other: &Proposition) -> bool {
+        if self.subject.is_empty() || self.subject != other.subject || self.comparator != other.comparator {
+            return false;
+        }
+
+        let threshold = match (&self.threshold, &other.threshold) {
+            (None, None) => Some(Ordering::Equal),
+            (Some(a), Some(b)) if a.unit.compatible(b.unit) => match self.comparator {
+                Some(Comparator::Above) => a.value.partial_cmp(&b.value),
+                Some(Comparator::Below) => b.value.partial_cmp(&a.value),
+                None => (a.value == b.value).then_some(Ordering::Equal),
+            },
+            _ => None,
+        };
+
+        let deadline = match (&self.deadline, &other.deadline) {
+            (None, None) => Some(Ordering::Equal),
+            (Some(a), Some(b)) => match (a.kind, b.kind) {
+                // Holding on an earlier date (or happening earlier) means it happened by the later one
+                (DeadlineKind::By, DeadlineKind::By) => b.date.partial_cmp(&a.date).filter(|o| o.is_ge()),
+                (DeadlineKind::On, DeadlineKind::By) => (a.date <= b.date).then_some(Ordering::Greater),
+                (DeadlineKind::On, DeadlineKind::On) => (a.date == b.date).then_some(Ordering::Equal),
+                (DeadlineKind::By, DeadlineKind::On) => None,
+            },
+            _ => None,
+        };
+
+        match (threshold, deadline) {
+            (Some(t), Some(d)) => t.is_ge() && d.is_ge() && (t.is_gt() || d.is_gt()),
+            _ => false,
+        }
+    }
+}
+
+const MONTHS: &[&str] = &["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+
+const STOP_WORDS: &[&str] = &[
+    "will", "would", "does", "do", "did", "is", "are", "be", "been", "the", "a", "an", "of", "to",
+    "by", "before", "on", "in", "at", "until", "end", "and", "for", "this", "year", "its", "it",
+    "price", "value", "close", "closes", "trade", "trades", "trading", "level", "mark", "market",
+];
+
+// Ticker-style aliases collapse onto one subject name
+const SUBJECT_ALIASES: &[(&str, &str)] = &[
+    ("btc", "bitcoin"),
+    ("eth", "ethereum"),
+    ("sol", "solana"),
+    ("doge", "dogecoin"),
+    ("tsla", "tesla"),
+    ("nvda", "nvidia"),
+    ("aapl", "apple"),
+    ("spx", "s&p 500"),
+    ("sp500", "s&p 500"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connective {
+    /// "A and B", "both A and B", parlays ("yes A, yes B")
+    And,
+    /// "A or B", "either A or B"
+    Or,
+}
+
+/// One clause of a compound question; `negated` for parlay legs like "no Chiefs"
+#[derive(Debug, Clone)]
+pub struct CompoundPart {
+    pub proposition: Proposition,
+    pub negated: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Compound {
+    pub connective: Connective,
+    pub parts: Vec<CompoundPart>,
+}
+
+// "$100k or more" is a threshold, not a disjunction
+const OR_SUFFIXES: &[&str] = &["more", "higher", "above", "less", "lower", "below", "fewer", "greater"];
+
+pub struct PropositionParser {
+    iso_re: Regex,
+    month_re: Regex,
+    quarter_re: Regex,
+    year_re: Regex,
+    relative_year_re: Regex,
+    threshold_re: Regex,
+    bracket_re: Regex,
+    comparator_re: Regex,
+    word_re: Regex,
+    thousands_re: Regex,
+    and_re: Regex,
+    or_re: Regex,
+    lead_re: Regex,
+}
+
+impl PropositionParser {
+    pub fn new() -> Self {
+        let prep = r"(?:\b(?P<prep>by the end of|by end of|at the end of|end of|before|until|by|on|in|at|during)\s+(?:the\s+)?)?";
+        let month = r"(?P<month>jan(?:uary)?|feb(?:ruary)?|mar(?:ch)?|apr(?:il)?|may|june?|july?|aug(?:ust)?|sep(?:t(?:ember)?)?|oct(?:ober)?|nov(?:ember)?|dec(?:ember)?)\b\.?";
+
+        Self {
+            iso_re: Regex::new(&format!(r"{}\b(?P<year>20\d{{2}})-(?P<month>\d{{2}})-(?P<day>\d{{2}})\b", prep)).unwrap(),
+            month_re: Regex::new(&format!(
+                r"{}\b{}(?:\s+(?P<day>\d{{1,2}})(?:st|nd|rd|th)?\b)?(?:,?\s+(?P<year>20\d{{2}})\b)?",
+                prep, month
+            )).unwrap(),
+            quarter_re: Regex::new(&format!(r"{}\bq(?P<quarter>[1-4])\b(?:\s+(?P<year>20\d{{2}})\b)?", prep)).unwrap(),
+            year_re: Regex::new(&format!(r"{}\b(?P<year>20\d{{2}})\b", prep)).unwrap(),
+            relative_year_re: Regex::new(r"(?P<prep>by the end of|by end of|at the end of|end of|before|by|in)\s+(?:this|the)\s+year\b").unwrap(),
+            threshold_re: Regex::new(
+                r"(?P<cmp>more than|greater than|higher than|at least|less than|lower than|at most|fall(?:s)? below|dip(?:s)? (?:to|below)|drop(?:s)? (?:to|below)|fall(?:s)? to|above|over|exceed(?:s)?|reach(?:es)?|hit(?:s)?|surpass(?:es)?|top(?:s)?|below|under|>=|<=|>|<|≥|≤)?\s*(?P<dollar>\$)?\s*(?P<num>\d[\d,]*(?:\.\d+)?)\s*(?P<scale>k|m|b|bn|t|thousand|million|billion|trillion)?\b\s*(?:°[fc]?)?\s*(?P<pct>%|percent)?\s*(?P<suffix>\+|or more|or higher|or above|or less|or lower|or below)?"
+            ).unwrap(),
+            bracket_re: Regex::new(
+                r"(?:\bbetween\s+)?(?P<lo_dollar>\$)?\s*(?P<lo>\d[\d,]*(?:\.\d+)?)\s*(?P<lo_scale>k|m|b|bn|t|thousand|million|billion|trillion)?\b\s*(?P<lo_pct>%)?\s*(?:-|–|\bto\b|\band\b)\s*(?P<hi_dollar>\$)?\s*(?P<hi>\d[\d,]*(?:\.\d+)?)\s*(?P<hi_scale>k|m|b|bn|t|thousand|million|billion|trillion)?\b\s*(?:°[fc]?)?\s*(?P<hi_pct>%|percent)?"
+            ).unwrap(),
+            comparator_re: Regex::new(r"\b(more than|greater than|higher than|at least|less than|lower than|at most|above|over|exceeds?|reach(?:es)?|hits?|surpass(?:es)?|tops?|below|under|falls?|dips?|drops?)\b").unwrap(),
+            word_re: Regex::new(r"[a-z0-9&]+").unwrap(),
+            thousands_re: Regex::new(r"(\d),(\d{3})\b").unwrap(),
+            and_re: Regex::new(r"\s*(?:\band\b|&|,)\s*").unwrap(),
+            or_re: Regex::new(r"\s*\bor\b\s*").unwrap(),
+            lead_re: Regex::new(r"\b(?:both|either)\b").unwrap(),
+        }
+    }
+
+    /// Parse a market question. `reference` fills in the year for deadlines
+    /// like "by March 31" (usually the market's close date).
+    pub fn parse(&self, text: &str, reference: Option<NaiveDate>) -> Option<Proposition> {
+        let mut text = text.to_lowercase();
+        let reference = reference.unwrap_or_else(|| Utc::now().date_naive());
+
+        let deadline = self.extract_deadline(&mut text, reference);
+
+        let (comparator, threshold) = match self.extract_threshold(&mut text) {
+            Some((cmp, t)) => (cmp, Some(t)),
+            None => (None, None),
+        };
+
+        let text = self.comparator_re.replace_all(&text, " ");
+        let mut words: Vec<String> = Vec::new();
+        for word in self.word_re.find_iter(&text).map(|m| m.as_str()) {
+            if STOP_WORDS.contains(&word) {
+                continue;
+            }
+            let word = SUBJECT_ALIASES.iter()
+                .find(|(alias, _)| *alias == word)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_else(|| word.to_string());
+            if !words.contains(&word) {
+                words.push(word);
+            }
+        }
+
+        if words.is_empty() {
+            return None;
+        }
+
+        Some(Proposition {
+            subject: words.join(" "),
+            comparator,
+            threshold,
+            deadline,
+        })
+    }
+
+    /// Split "A and B" / "either A or B" / "yes A, no B" into parsed clauses.
+    /// A deadline stated once ("A and B by June 30") applies to every clause.
+    /// Mixed connectives and numeric ranges ("between 3% and 4%") are not compounds.
+    pub fn parse_compound(&self, text: &str, reference: Option<NaiveDate>) -> Option<Compound> {
+        let mut text = self.thousands_re.replace_all(&text.to_lowercase(), "$1$2").to_string();
+        if self.extract_bracket(&mut text.clone()).is_some() {
+            return None;
+        }
+        let deadline = self.extract_deadline(&mut text, reference.unwrap_or_else(|| Utc::now().date_naive()));
+        let text = self.lead_re.replace_all(&text, " ").replace('?', " ");
+
+        let ors: Vec<_> = self.or_re.find_iter(&text)
+            .filter(|m| {
+                let next = text[m.end()..].split_whitespace().next().unwrap_or("");
+                !OR_SUFFIXES.contains(&next)
+            })
+            .collect();
+        let ands: Vec<_> = self.and_re.find_iter(&text).collect();
+        let (connective, separators) = match (ands.is_empty(), ors.is_empty()) {
+            (false, true) => (Connective::And, ands),
+            (true, false) => (Connective::Or, ors),
+            _ => return None,
+        };
+
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        for sep in &separators {
+            pieces.push(&text[start..sep.start()]);
+            start = sep.end();
+        }
+        pieces.push(&text[start..]);
+
+        let parts = pieces.into_iter()
+            .map(|piece| {
+                let piece = piece.trim();
+                let (negated, piece) = match piece.strip_prefix("no ") {
+                    Some(rest) => (true, rest),
+                    None => (false, piece.strip_prefix("yes ").unwrap_or(piece)),
+                };
+                let mut proposition = self.parse(piece, reference)?;
+                if proposition.deadline.is_none() {
+                    proposition.deadline = deadline;
+                }
+                Some(CompoundPart { proposition, negated })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Compound { connective, parts })
+    }
+
+    fn extract_deadline(&self, text: &mut String, reference: NaiveDate) -> Option<Deadline> {
+        let kind_of = |caps: &Captures| match caps.name("prep").map(|p| p.as_str()) {
+            Some("on") | Some("at") | Some("end of") | Some("at the end of") => DeadlineKind::On,
+            _ => DeadlineKind::By,
+        };
+        let before = |caps: &Captures| caps.name("prep").map(|p| p.as_str()) == Some("before");
+        let year_of = |caps: &Captures| caps.name("year")
+            .and_then(|y| y.as_str().parse::<i32>().ok())
+            .unwrap_or(reference.year());
+
+        let mut found: Option<(std::ops::Range<usize>, Deadline)> = None;
+
+        if let Some(caps) = self.iso_re.captures(text) {
+            let date = NaiveDate::from_ymd_opt(
+                year_of(&caps),
+                caps["month"].parse().unwrap_or(0),
+                caps["day"].parse().unwrap_or(0),
+            );
+            if let Some(date) = date {
+                let date = if before(&caps) { date - Duration::days(1) } else { date };
+                found = Some((caps.get(0).unwrap().range(), Deadline { date, kind: kind_of(&caps) }));
+            }
+        }
+
+        if found.is_none() {
+            for caps in self.month_re.captures_iter(text) {
+                // A lone month word ("may", "Mar-a-Lago") needs a preposition, day or year
+                if caps.name("day").is_none() && caps.name("year").is_none() && caps.name("prep").is_none() {
+                    continue;
+                }
+                let Some(month) = MONTHS.iter().position(|m| caps["month"].starts_with(m)) else {
+                    continue;
+                };
+                let month = month as u32 + 1;
+                let year = year_of(&caps);
+                let date = match caps.name("day").and_then(|d| d.as_str().parse::<u32>().ok()) {
+                    Some(day) => {
+                        let date = NaiveDate::from_ymd_opt(year, month, day);
+                        if before(&caps) { date.map(|d| d - Duration::days(1)) } else { date }
+                    }
+                    None if before(&caps) => NaiveDate::from_ymd_opt(year, month, 1).map(|d| d - Duration::days(1)),
+                    None => last_day_of_month(year, month),
+                };
+                if let Some(date) = date {
+                    found = Some((caps.get(0).unwrap().range(), Deadline { date, kind: kind_of(&caps) }));
+                    break;
+                }
+            }
+        }
+
+        if found.is_none() {
+            if let Some(caps) = self.quarter_re.captures(text) {
+                let quarter: u32 = caps["quarter"].parse().unwrap_or(4);
+                let year = year_of(&caps);
+                let date = if before(&caps) {
+                    NaiveDate::from_ymd_opt(year, (quarter - 1) * 3 + 1, 1).map(|d| d - Duration::days(1))
+                } else {
+                    last_day_of_month(year, quarter * 3)
+                };
+                if let Some(date) = date {
+                    found = Some((caps.get(0).unwrap().range(), Deadline { date, kind: kind_of(&caps) }));
+                }
+            }
+        }
+
+        if found.is_none() {
+            for caps in self.year_re.captures_iter(text) {
+                // A bare year ("the 2028 election") is part of the subject, not a deadline
+                if caps.name("prep").is_none() {
+                    continue;
+                }
+                let year = year_of(&caps);
+                let date = if before(&caps) {
+                    NaiveDate::from_ymd_opt(year - 1, 12, 31)
+                } else {
+                    NaiveDate::from_ymd_opt(year, 12, 31)
+                };
+                if let Some(date) = date {
+                    found = Some((caps.get(0).unwrap().range(), Deadline { date, kind: kind_of(&caps) }));
+                    break;
+                }
+            }
+        }
+
+        if found.is_none() {
+            if let Some(caps) = self.relative_year_re.captures(text) {
+                if let Some(date) = NaiveDate::from_ymd_opt(reference.year(), 12, 31) {
+                    found = Some((caps.get(0).unwrap().range(), Deadline { date, kind: kind_of(&caps) }));
+                }
+            }
+        }
+
+        let (range, deadline) = found?;
+        text.replace_range(range, " ");
+        Some(deadline)
+    }
+
+    fn extract_threshold(&self, text: &mut String) -> Option<(Option<Comparator>, Threshold)> {
+        for caps in self.threshold_re.captures_iter(text) {
+            let has_marker = caps.name("cmp").is_some() || caps.name("dollar").is_some()
+                || caps.name("scale").is_some() || caps.name("pct").is_some() || caps.name("suffix").is_some();
+            if !has_marker {
+                continue; // Plain counts ("3 cuts") stay in the subject
+            }
+
+            let Ok(mut value) = caps["num"].replace(',', "").parse::<f64>() else {
+                continue;
+            };
+            value *= scale_factor(caps.name("scale").map(|s| s.as_str()));
+
+            let unit = if caps.name("pct").is_some() {
+                ThresholdUnit::Percent
+            } else if caps.name("dollar").is_some() {
+                ThresholdUnit::Dollar
+            } else {
+                ThresholdUnit::Plain
+            };
+
+            let cmp_word = caps.name("cmp").or(caps.name("suffix")).map(|c| c.as_str());
+            let comparator = match cmp_word {
+                Some(w) if ["less", "lower", "most", "below", "under", "fall", "dip", "drop", "<", "≤"]
+                    .iter().any(|k| w.contains(k)) => Some(Comparator::Below),
+                Some(_) => Some(Comparator::Above),
+                // Bare price targets ("Bitcoin $150k in 2025") read as "reaches"
+                None if unit != ThresholdUnit::Percent => Some(Comparator::Above),
+                None => None,
+            };
+
+            let range = caps.get(0).unwrap().range();
+            text.replace_range(range, " ");
+            return Some((comparator, Threshold { value, unit }));
+        }
+        None
+    }
+
+    /// Pull a range like "$90,000 to $94,999.99", "between 3% and 3.5%" or "$90-95k"
+    /// out of `text` (lowercased), returning the lower and upper bounds.
+    /// Unmarked ranges ("2025-2026", ISO dates) are left alone.
+    pub fn extract_bracket(&self, text: &mut String) -> Option<(Threshold, Threshold)> {
+        for caps in self.bracket_re.captures_iter(text) {
+            let dollar = caps.name("lo_dollar").is_some() || caps.name("hi_dollar").is_some();
+            let pct = caps.name("lo_pct").is_some() || caps.name("hi_pct").is_some();
+            let lo_scale = caps.name("lo_scale").map(|s| s.as_str());
+            let hi_scale = caps.name("hi_scale").map(|s| s.as_str());
+            if !dollar && !pct && lo_scale.is_none() && hi_scale.is_none() {
+                continue;
+            }
+
+            let (Ok(lo), Ok(hi)) = (
+                caps["lo"].replace(',', "").parse::<f64>(),
+                caps["hi"].replace(',', "").parse::<f64>(),
+            ) else {
+                continue;
+            };
+            // "$90-95k": the lower bound borrows the upper bound's scale
+            let lo = lo * scale_factor(lo_scale.or(hi_scale));
+            let hi = hi * scale_factor(hi_scale);
+            if lo >= hi {
+                continue;
+            }
+
+            let unit = if pct {
+                ThresholdUnit::Percent
+            } else if dollar {
+                ThresholdUnit::Dollar
+            } else {
+                ThresholdUnit::Plain
+            };
+
+            let range = caps.get(0).unwrap().range();
+            text.replace_range(range, " ");
+            return Some((Threshold { value: lo, unit }, Threshold { value: hi, unit }));
+        }
+        None
+    }
+}
+
+fn scale_factor(scale: Option<&str>) -> f64 {
+    match scale {
+        Some("k") | Some("thousand") => 1e3,
+        Some("m") | Some("million") => 1e6,
+        Some("b") | Some("bn") | Some("billion") => 1e9,
+        Some("t") | Some("trillion") => 1e12,
+        _ => 1.0,
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1).map(|d| d - Duration::days(1))
+}
+
+#[derive(Debug, Deserialize)]
+struct CorpusFile {
+    propositions: Vec<CorpusProposition>,
+    implications: Vec<CorpusImplication>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CorpusProposition {
+    text: String,
+    reference_date: Option<NaiveDate>,
+    expected: Option<Proposition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CorpusImplication {
+    a: String,
+    b: String,
+    reference_date: Option<NaiveDate>,
+    implies: bool,
+}
+
+/// Run the parser over a labeled corpus of question texts and report mismatches.
+/// Returns true when every entry matches its label.
+pub fn check_corpus(path: &str) -> bool {
+    let corpus: CorpusFile = match fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|c| serde_json::from_str(&c).map_err(|e| e.to_string()))
+    {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("❌ Failed to load corpus {}: {}", path, e);
+            return false;
+        }
+    };
+
+    let parser = PropositionParser::new();
+    let mut failures = 0;
+
+    for entry in &corpus.propositions {
+        let parsed = parser.parse(&entry.text, entry.reference_date);
+        if parsed != entry.expected {
+            failures += 1;
+            println!("✗ {}\n    expected: {:?}\n    parsed:   {:?}", entry.text, entry.expected, parsed);
+        }
+    }
+
+    for entry in &corpus.implications {
+        let a = parser.parse(&entry.a, entry.reference_date);
+        let b = parser.parse(&entry.b, entry.reference_date);
+        let implies = matches!((&a, &b), (Some(a), Some(b)) if a.implies(b));
+        if implies != entry.implies {
+            failures += 1;
+            println!("✗ '{}' ⇒ '{}': expected {}, got {}", entry.a, entry.b, entry.implies, implies);
+        }
+    }
+
+    let total = corpus.propositions.len() + corpus.implications.len();
+    println!("📋 Proposition corpus: {}/{} passed", total - failures, total);
+    failures == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labeled_corpus_passes() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/propositions_corpus.json");
+        assert!(check_corpus(path), "proposition corpus has mismatches (see output above)");
+    }
+}
@@ -0,0 +1,183 @@
+use crate::engine::Market;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+// Resolution sources named in rules text, canonicalized; patterns match whole words
+const KNOWN_SOURCES: &[(&str, &str)] = &[
+    ("coinbase", r"\bcoinbase\b"),
+    ("binance", r"\bbinance\b"),
+    ("coingecko", r"\bcoingecko\b"),
+    ("coinmarketcap", r"\bcoinmarketcap\b"),
+    ("chainlink", r"\bchainlink\b"),
+    ("cme", r"\bcme\b"),
+    ("associated press", r"\bassociated press\b|\bap news\b|\bthe ap\b"),
+    ("fox news", r"\bfox news\b"),
+    ("nbc", r"\bnbc news\b"),
+    ("cnn", r"\bcnn\b"),
+    ("bls", r"\bbureau of labor statistics\b|\bbls\b"),
+    ("bea", r"\bbureau of economic analysis\b|\bbea\b"),
+    ("federal reserve", r"\bfederal reserve\b|\bfomc\b"),
+    ("nba", r"\bnba\.com\b"),
+    ("nfl", r"\bnfl\.com\b"),
+    ("espn", r"\bespn\b"),
+    ("nasdaq", r"\bnasdaq\b"),
+    ("nyse", r"\bnyse\b"),
+    ("bloomberg", r"\bbloomberg\b"),
+    ("reuters", r"\breuters\b"),
+];
+
+/// Edge-case clauses that change the payout when only one venue has them
+const EDGE_CASES: &[(&str, &str)] = &[
+    ("postponement", r"postpone|delay|reschedul"),
+    ("cancellation", r"cancel|\bvoid\b|called off"),
+    ("tie", r"\btie\b|\bdraw\b|dead heat"),
+    ("recount", r"recount|certif"),
+    ("intraday", r"at any (time|point)|intraday|any minute"),
+    ("closing_price", r"closing price|\bat close\b|settlement price"),
+    ("revision", r"revis|initial release|first release|advance estimate"),
+    ("overtime", r"overtime|extra time|penalt"),
+    ("timezone", r"\b(et|est|edt|pt|pst|utc|gmt)\b"),
+];
+
+const MISSING_RULES_RISK: f64 = 0.15;
+const SOURCE_MISMATCH_RISK: f64 = 0.35;
+const CUTOFF_GAP_RISK: f64 = 0.25; // reached at a 30-day gap
+const EDGE_CASE_RISK: f64 = 0.1;   // per one-sided clause
+
+/// How the resolution rules of two "same" markets differ
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResolutionDiff {
+    pub sources_a: Vec<String>,
+    pub sources_b: Vec<String>,
+    /// Both sides name a source and none are shared
+    pub source_mismatch: bool,
+    pub cutoff_gap_days: Option<i64>,
+    /// Edge-case clauses present on only one side
+    pub only_a: Vec<String>,
+    pub only_b: Vec<String>,
+    pub missing_rules: bool,
+    /// 0 = same contract, 1 = almost certainly resolves differently
+    pub risk: f64,
+}
+
+impl ResolutionDiff {
+    /// Short human-readable list of what differs
+    pub fn flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if self.missing_rules {
+            flags.push("rules missing on one venue".to_string());
+        }
+        if self.source_mismatch {
+            flags.push(format!("sources differ ({} vs {})", self.sources_a.join("/"), self.sources_b.join("/")));
+        }
+        if let Some(gap) = self.cutoff_gap_days.filter(|g| *g > 0) {
+            flags.push(format!("cutoffs {}d apart", gap));
+        }
+        for clause in &self.only_a {
+            flags.push(format!("only A covers {}", clause));
+        }
+        for clause in &self.only_b {
+            flags.push(format!("only B covers {}", clause));
+        }
+        flags
+    }
+
+    /// Risk from the rules text alone, for pairs whose cutoffs differ on purpose (calendar spreads)
+    pub fn rules_risk(&self) -> f64 {
+        let mut risk = 0.0;
+        if self.missing_rules {
+            risk += MISSING_RULES_RISK;
+        }
+        if self.source_mismatch {
+            risk += SOURCE_MISMATCH_RISK;
+        }
+        risk += EDGE_CASE_RISK * (self.only_a.len() + self.only_b.len()) as f64;
+        risk.min(1.0)
+    }
+}
+
+pub struct ResolutionComparer {
+    sources: Vec<(&'static str, Regex)>,
+    edge_cases: Vec<(&'static str, Regex)>,
+}
+
+fn compile(patterns: &[(&'static str, &str)]) -> Vec<(&'static str, Regex)> {
+    patterns.iter()
+        .map(|(name, pattern)| (*name, Regex::new(&format!("(?i){}", pattern)).unwrap()))
+        .collect()
+}
+
+impl ResolutionComparer {
+    pub fn new() -> Self {
+        Self { sources: compile(KNOWN_SOURCES), edge_cases: compile(EDGE_CASES) }
+    }
+
+    pub fn compare(&self, a: &Market, b: &Market) -> ResolutionDiff {
+        let text_a = resolution_text(a);
+        let text_b = resolution_text(b);
+
+        let sources_a = self.sources(&text_a);
+        let sources_b = self.sources(&text_b);
+        let source_mismatch = !sources_a.is_empty() && !sources_b.is_empty()
+            && sources_a.is_disjoint(&sources_b);
+
+        let cutoff_gap_days = match (a.close_time(), b.close_time()) {
+            (Some(ca), Some(cb)) => Some((ca - cb).num_days().abs()),
+            _ => None,
+        };
+
+        let missing_rules = text_a.is_empty() || text_b.is_empty();
+        let (only_a, only_b) = if missing_rules {
+            (Vec::new(), Vec::new())
+        } else {
+            let cases_a = self.edge_cases(&text_a);
+            let cases_b = self.edge_cases(&text_b);
+            (
+                cases_a.difference(&cases_b).map(|s| s.to_string()).collect(),
+                cases_b.difference(&cases_a).map(|s| s.to_string()).collect(),
+            )
+        };
+
+        let mut diff = ResolutionDiff {
+            sources_a: sources_a.into_iter().collect(),
+            sources_b: sources_b.into_iter().collect(),
+            source_mismatch,
+            cutoff_gap_days,
+            only_a,
+            only_b,
+            missing_rules,
+            risk: 0.0,
+        };
+
+        let cutoff_risk = cutoff_gap_days
+            .map(|gap| CUTOFF_GAP_RISK * (gap as f64 / 30.0).min(1.0))
+            .unwrap_or(0.0);
+        diff.risk = (diff.rules_risk() + cutoff_risk).min(1.0);
+        diff
+    }
+
+    fn sources(&self, text: &str) -> BTreeSet<String> {
+        self.sources.iter()
+            .filter(|(_, re)| re.is_match(text))
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    fn edge_cases(&self, text: &str) -> BTreeSet<&'static str> {
+        self.edge_cases.iter()
+            .filter(|(_, re)| re.is_match(text))
+            .map(|(name, _)| *name)
+            .collect()
+    }
+}
+
+/// Rules and resolution source, lowercased ("" when the venue gave neither)
+fn resolution_text(market: &Market) -> String {
+    let parts: Vec<&str> = [market.rules.as_deref(), market.resolution_source.as_deref()]
+        .into_iter()
+        .flatten()
+        .filter(|s| !s.trim().is_empty())
+        .collect();
+    parts.join("\n").to_lowercase()
+}
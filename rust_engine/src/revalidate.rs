@@ -0,0 +1,110 @@
+use crate::engine::{Confirmation, Market, Opportunity, UNDATED_HORIZON_DAYS};
+use crate::kalshi_fetcher::KalshiFetcher;
+use crate::manifold_fetcher::ManifoldFetcher;
+use crate::polymarket_fetcher::PolymarketFetcher;
+use futures::future::join_all;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::time::Instant;
+
+/// Why an opportunity was not confirmed
+pub enum Rejection {
+    /// Fresh prices no longer clear the strategy's profit floor
+    Faded { net_profit: f64 },
+    /// A leg's market could not be refetched or priced
+    Unavailable { reason: String },
+}
+
+/// Refetches the markets behind an opportunity's legs right before alerting,
+/// since the batch snapshot can be many seconds old by then
+pub struct Revalidator<'a> {
+    pub polymarket: &'a PolymarketFetcher,
+    pub kalshi: &'a KalshiFetcher,
+    pub manifold: &'a ManifoldFetcher,
+}
+
+impl Revalidator<'_> {
+    async fn fetch(&self, market_key: &str) -> Result<Market, Box<dyn Error>> {
+        let (platform, id) = market_key.split_once(':').ok_or("malformed market key")?;
+        match platform {
+            "Polymarket" => self.polymarket.fetch_market(id).await,
+            "Kalshi" => self.kalshi.fetch_market(id).await,
+            "Manifold" => self.manifold.fetch_market(id).await,
+            other => Err(format!("no refetch for {}", other).into()),
+        }
+    }
+
+    /// Refetch every leg's market (all at once) and keep the opportunity only if it still nets
+    /// `min_profit` at the fresh prices (see `reprice`). On success
+    /// `confirmation` is set as well.
+    pub async fn confirm(&self, opp: &mut Opportunity, snapshot: Instant, min_profit: f64) -> Result<(), Rejection> {
+        let refetch_start = Instant::now();
+
+        let keys: HashSet<&str> = opp.legs.iter().map(|l| l.market_key.as_str()).collect();
+        let fetched = join_all(keys.into_iter().map(|key| async move { (key, self.fetch(key).await) })).await;
+        let mut fresh: HashMap<String, Market> = HashMap::new();
+        for (key, market) in fetched {
+            let market = market.map_err(|e| Rejection::Unavailable { reason: format!("{}: {}", key, e) })?;
+            fresh.insert(key.to_string(), market);
+        }
+        let refetch_ms = refetch_start.elapsed().as_millis();
+
+        let snapshot_net_profit = opp.net_profit_after_fees;
+        reprice(opp, &fresh, min_profit)?;
+        opp.confirmation = Some(Confirmation {
+            snapshot_to_confirm_ms: snapshot.elapsed().as_millis(),
+            refetch_ms,
+            snapshot_net_profit,
+        });
+        Ok(())
+    }
+}
+
+/// Reprice every leg in `fresh` copies of its market (same sizes, same payout)
+/// and keep the opportunity only if it still nets `min_profit`. On success the
+/// legs, costs and returns are updated in place.
+pub fn reprice(opp: &mut Opportunity, fresh: &HashMap<String, Market>, min_profit: f64) -> Result<(), Rejection> {
+    let mut prices = Vec::with_capacity(opp.legs.len());
+    for leg in &opp.legs {
+        let Some(market) = fresh.get(&leg.market_key) else {
+            return Err(Rejection::Unavailable { reason: format!("{} is gone", leg.market_key) });
+        };
+        match leg.price_in(market) {
+            Some(p) if p > 0.0 => prices.push(p),
+            _ => return Err(Rejection::Unavailable { reason: format!("{} has no price", leg.market_key) }),
+        }
+    }
+
+    // The guaranteed payout depends on sizes only, not on prices
+    let payout = opp.total_cost + opp.gross_profit;
+    let mut total_cost = 0.0;
+    let mut total_fees = 0.0;
+    for (leg, price) in opp.legs.iter().zip(&prices) {
+        let fee_rate = if leg.cost() > 0.0 { leg.fee / leg.cost() } else { 0.0 };
+        total_cost += price * leg.size;
+        total_fees += price * leg.size * fee_rate;
+    }
+    let net_profit = payout - total_cost - total_fees;
+    if net_profit < min_profit {
+        return Err(Rejection::Faded { net_profit });
+    }
+
+    for (leg, price) in opp.legs.iter_mut().zip(prices) {
+        let fee_rate = if leg.cost() > 0.0 { leg.fee / leg.cost() } else { 0.0 };
+        leg.limit_price = price;
+        leg.fee = leg.cost() * fee_rate;
+        let market = &fresh[&leg.market_key];
+        leg.fetched_at = market.fetched_at;
+        leg.venue_time = market.venue_time;
+    }
+
+    let roi = net_profit / total_cost * 100.0;
+    let days = opp.days_to_resolution.unwrap_or(UNDATED_HORIZON_DAYS);
+    opp.annualized_roi_percent = opp.annualized_roi_percent
+        .map(|annualized| annualized + (roi - opp.roi_percent) * 365.0 / days);
+    opp.total_cost = total_cost;
+    opp.gross_profit = payout - total_cost;
+    opp.net_profit_after_fees = net_profit;
+    opp.roi_percent = roi;
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use crate::config::keywords_for_category;
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+
+const RULES_PATH: &str = "../rules.json";
+
+// Shipped copy, used when the rules file is missing or fails validation
+pub(crate) const DEFAULT_RULES: &str = include_str!("../../rules.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Relation {
+    Implies,
+    MutuallyExclusive,
+    Equivalent,
+}
+
+impl Relation {
+    /// Name used for `MarketDependency.dependency_type`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Relation::Implies => "implies",
+            Relation::MutuallyExclusive => "mutually_exclusive",
+            Relation::Equivalent => "equivalent",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    version: u32,
+    #[serde(default)]
+    subjects: Vec<String>,
+    #[serde(default)]
+    rules: Vec<RuleSpec>,
+    #[serde(default)]
+    winner_terms: Vec<String>,
+    #[serde(default)]
+    opposites: Vec<(String, String)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleSpec {
+    name: String,
+    relation: Relation,
+    a: ConditionSpec,
+    b: ConditionSpec,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    same_subject: bool,
+    #[serde(default)]
+    b_excludes_a: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct ConditionSpec {
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    regex: Option<String>,
+}
+
+/// Matches when any keyword is a substring of the text, or the regex matches
+#[derive(Debug)]
+pub struct Condition {
+    keywords: Vec<String>,
+    regex: Option<Regex>,
+}
+
+impl Condition {
+    pub fn matches(&self, text: &str) -> bool {
+        self.keywords.iter().any(|kw| text.contains(kw.as_str()))
+            || self.regex.as_ref().is_some_and(|re| re.is_match(text))
+    }
+}
+
+/// "If a market matches A and another matches B, they are related by `relation`"
+#[derive(Debug)]
+pub struct Rule {
+    pub name: String,
+    pub relation: Relation,
+    pub a: Condition,
+    pub b: Condition,
+    pub categories: Vec<String>,
+    /// Only pair markets that share an entry from `RuleSet::subjects`
+    pub same_subject: bool,
+    /// Skip B markets that also match A (they are the same kind of market)
+    pub b_excludes_a: bool,
+}
+
+impl Rule {
+    /// Category scoping: an unscoped rule applies everywhere
+    pub fn in_scope(&self, text: &str) -> bool {
+        self.categories.is_empty()
+            || self.categories.iter()
+                .any(|cat| keywords_for_category(cat).iter().any(|kw| text.contains(kw)))
+    }
+}
+
+#[derive(Debug)]
+pub struct RuleSet {
+    pub version: u32,
+    pub subjects: Vec<String>,
+    pub rules: Vec<Rule>,
+    /// Single-winner contests: questions differing only in the named entity are exclusive
+    pub winner_terms: Vec<String>,
+    /// Word pairs that flip an otherwise identical question ("win" / "lose")
+    pub opposites: Vec<(String, String)>,
+}
+
+impl RuleSet {
+    /// Load the rules file (re-read every cycle, like config.json)
+    pub fn load() -> Self {
+        Self::load_from(RULES_PATH)
+    }
+
+    pub fn load_from(path: &str) -> Self {
+        let parsed = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| Self::from_json(&content));
+
+        match parsed {
+            Ok(rules) => rules,
+            Err(e) => {
+                eprintln!("⚠️ Rules file {} unusable ({}), using built-in rules", path, e);
+                Self::from_json(DEFAULT_RULES).expect("built-in rules.json is invalid")
+            }
+        }
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let file: RulesFile = serde_json::from_str(content).map_err(|e| e.to_string())?;
+
+        let compile = |rule: &str, spec: ConditionSpec| -> Result<Condition, String> {
+            let regex = spec.regex
+                .map(|r| Regex::new(&format!("(?i){}", r)).map_err(|e| format!("rule '{}': {}", rule, e)))
+                .transpose()?;
+            if spec.keywords.is_empty() && regex.is_none() {
+                return Err(format!("rule '{}': condition needs keywords or a regex", rule));
+            }
+            Ok(Condition {
+                keywords: spec.keywords.into_iter().map(|k| k.to_lowercase()).collect(),
+                regex,
+            })
+        };
+
+        let mut rules = Vec::new();
+        for spec in file.rules {
+            rules.push(Rule {
+                a: compile(&spec.name, spec.a)?,
+                b: compile(&spec.name, spec.b)?,
+                name: spec.name,
+                relation: spec.relation,
+                categories: spec.categories,
+                same_subject: spec.same_subject,
+                b_excludes_a: spec.b_excludes_a,
+            });
+        }
+
+        Ok(Self {
+            version: file.version,
+            subjects: file.subjects.into_iter().map(|s| s.to_lowercase()).collect(),
+            rules,
+            winner_terms: file.winner_terms.into_iter().map(|s| s.to_lowercase()).collect(),
+            opposites: file.opposites.into_iter()
+                .map(|(a, b)| (a.to_lowercase(), b.to_lowercase()))
+                .collect(),
+        })
+    }
+
+    /// Report rules whose A or B side matches none of the given (lowercased) market texts.
+    /// Returns the number of dead rules.
+    pub fn report_unmatched(&self, texts: &[String]) -> usize {
+        let mut dead = 0;
+        for rule in &self.rules {
+            let scoped: Vec<&String> = texts.iter().filter(|t| rule.in_scope(t)).collect();
+            let a_count = scoped.iter().filter(|t| rule.a.matches(t)).count();
+            let b_count = scoped.iter().filter(|t| rule.b.matches(t)).count();
+
+            if a_count == 0 || b_count == 0 {
+                dead += 1;
+                println!("✗ {} ({}): A matches {}, B matches {}", rule.name, rule.relation.as_str(), a_count, b_count);
+            }
+        }
+
+        println!("📋 Rules v{}: {}/{} rules match live markets", self.version, self.rules.len() - dead, self.rules.len());
+        dead
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_rules_parse() {
+        let rules = RuleSet::from_json(DEFAULT_RULES).unwrap();
+        assert_eq!(rules.version, 1);
+        assert!(rules.rules.iter().any(|r| r.name == "trump-win-implies-republican-win"));
+        assert!(rules.opposites.contains(&("win".to_string(), "lose".to_string())));
+    }
+
+    #[test]
+    fn conditions_are_lowercased_and_regex_is_case_insensitive() {
+        let rules = RuleSet::from_json(r#"{
+            "version": 2,
+            "subjects": ["BTC"],
+            "rules": [{
+                "name": "cut", "relation": "mutually_exclusive", "categories": ["economics"],
+                "a": {"keywords": ["Fed Cut"]},
+                "b": {"regex": "rates? (hold|unchanged)"}
+            }]
+        }"#).unwrap();
+        assert_eq!(rules.subjects, vec!["btc"]);
+
+        let rule = &rules.rules[0];
+        assert_eq!(rule.relation, Relation::MutuallyExclusive);
+        assert!(rule.a.matches("will the fed cut in march?"));
+        assert!(rule.b.matches("Rates Unchanged in March?"));
+        assert!(!rule.b.matches("rates rise in march?"));
+        assert!(rule.in_scope("will the fed cut rates?"));
+        assert!(!rule.in_scope("will the lakers win?"));
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let no_condition = r#"{"version": 1, "rules": [
+            {"name": "empty", "relation": "implies", "a": {"keywords": ["x"]}, "b": {}}
+        ]}"#;
+        let err = RuleSet::from_json(no_condition).unwrap_err();
+        assert!(err.contains("rule 'empty'") && err.contains("keywords or a regex"), "{}", err);
+
+        let bad_regex = r#"{"version": 1, "rules": [
+            {"name": "broken", "relation": "implies", "a": {"regex": "(unclosed"}, "b": {"keywords": ["y"]}}
+        ]}"#;
+        assert!(RuleSet::from_json(bad_regex).unwrap_err().contains("rule 'broken'"));
+
+        let bad_relation = r#"{"version": 1, "rules": [
+            {"name": "r", "relation": "contradicts", "a": {"keywords": ["x"]}, "b": {"keywords": ["y"]}}
+        ]}"#;
+        assert!(RuleSet::from_json(bad_relation).is_err());
+        assert!(RuleSet::from_json(r#"{"rules": []}"#).is_err(), "version is required");
+    }
+
+    #[test]
+    fn unusable_file_falls_back_to_built_in_rules() {
+        let rules = RuleSet::load_from("/nonexistent/rules.json");
+        assert_eq!(rules.rules.len(), RuleSet::from_json(DEFAULT_RULES).unwrap().rules.len());
+    }
+
+    #[test]
+    fn dead_rules_are_counted() {
+        let rules = RuleSet::from_json(r#"{"version": 1, "rules": [
+            {"name": "live", "relation": "implies", "a": {"keywords": ["sweep"]}, "b": {"keywords": ["win series"]}},
+            {"name": "dead", "relation": "implies", "a": {"keywords": ["landslide"]}, "b": {"keywords": ["win"]}}
+        ]}"#).unwrap();
+        let texts = vec!["celtics sweep".to_string(), "celtics win series".to_string()];
+        assert_eq!(rules.report_unmatched(&texts), 1);
+    }
+}
@@ -0,0 +1,486 @@
+use crate::config::StorageConfig;
+use crate::lifecycle::TrackedOpportunity;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+/// Schema changes in order; `PRAGMA user_version` records how many have run
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    "CREATE TABLE alerts (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        sent_at TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        target TEXT NOT NULL,
+        roi_percent REAL,
+        net_profit REAL,
+        payload TEXT NOT NULL
+    );
+    CREATE INDEX alerts_sent_at ON alerts (sent_at);
+    CREATE TABLE lifecycle (
+        id TEXT NOT NULL,
+        first_seen TEXT NOT NULL,
+        last_seen TEXT NOT NULL,
+        peak_roi REAL NOT NULL,
+        current_roi REAL NOT NULL,
+        alerted_roi REAL,
+        closed_at TEXT,
+        opportunity TEXT NOT NULL,
+        PRIMARY KEY (id, first_seen)
+    );
+    CREATE TABLE signals (
+        id TEXT PRIMARY KEY
+    );
+    CREATE TABLE match_verdicts (
+        market_a TEXT NOT NULL,
+        market_b TEXT NOT NULL,
+        confirmed INTEGER NOT NULL,
+        decided_at TEXT NOT NULL,
+        PRIMARY KEY (market_a, market_b)
+    );
+    CREATE TABLE mutes (
+        target TEXT PRIMARY KEY,
+        until TEXT,
+        created_at TEXT NOT NULL
+    );",
+];
+
+/// One alert that went out
+#[derive(Debug, Clone)]
+pub struct AlertRecord {
+    pub sent_at: DateTime<Utc>,
+    /// "opened", "changed", "closed", "cross_match" or "divergence"
+    pub kind: String,
+    /// Opportunity, match or divergence id
+    pub target: String,
+    pub roi_percent: Option<f64>,
+    pub net_profit: Option<f64>,
+    /// The alerted object as JSON
+    pub payload: String,
+}
+
+/// Silences alerts for an opportunity id, market key or event key
+#[derive(Debug, Clone)]
+pub struct Mute {
+    pub target: String,
+    /// None = until unmuted
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Human verdicts on CrossMatcher pairs, keyed by sorted market keys
+#[derive(Debug, Clone, Default)]
+pub struct MatchVerdicts {
+    verdicts: HashMap<(String, String), bool>,
+}
+
+impl MatchVerdicts {
+    pub fn pair(key_a: &str, key_b: &str) -> (String, String) {
+        if key_a <= key_b {
+            (key_a.to_string(), key_b.to_string())
+        } else {
+            (key_b.to_string(), key_a.to_string())
+        }
+    }
+
+    /// Some(true) = confirmed same event, Some(false) = rejected
+    pub fn get(&self, key_a: &str, key_b: &str) -> Option<bool> {
+        self.verdicts.get(&Self::pair(key_a, key_b)).copied()
+    }
+}
+
+/// Everything that has to survive a restart
+pub trait Store {
+    fn record_alert(&mut self, alert: &AlertRecord) -> Result<(), Box<dyn Error>>;
+
+    /// Upsert tracked opportunities (open ones and those that just closed)
+    fn save_lifecycle(&mut self, records: &[&TrackedOpportunity]) -> Result<(), Box<dyn Error>>;
+    /// Opportunities still open at last save
+    fn load_lifecycle(&self) -> Result<Vec<TrackedOpportunity>, Box<dyn Error>>;
+
+    /// Replace the set of already-alerted signal ids
+    fn save_signals(&mut self, ids: &HashSet<String>) -> Result<(), Box<dyn Error>>;
+    fn load_signals(&self) -> Result<HashSet<String>, Box<dyn Error>>;
+
+    fn set_match_verdict(&mut self, key_a: &str, key_b: &str, confirmed: bool) -> Result<(), Box<dyn Error>>;
+    fn match_verdicts(&self) -> Result<MatchVerdicts, Box<dyn Error>>;
+
+    fn add_mute(&mut self, mute: &Mute) -> Result<(), Box<dyn Error>>;
+    /// False if the target wasn't muted
+    fn remove_mute(&mut self, target: &str) -> Result<bool, Box<dyn Error>>;
+    fn active_mutes(&self, now: DateTime<Utc>) -> Result<Vec<Mute>, Box<dyn Error>>;
+
+    /// Drop old alerts, closed lifecycle rows and expired mutes; returns rows deleted
+    fn prune(&mut self, retention: &StorageConfig, now: DateTime<Utc>) -> Result<usize, Box<dyn Error>>;
+}
+
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    pub fn open(path: &str) -> Result<Self, Box<dyn Error>> {
+        let mut conn = Connection::open(path)?;
+        migrate(&mut conn, MIGRATIONS, "State store")?;
+        Ok(Self { conn })
+    }
+}
+
+/// Run the migrations past the database's `user_version`, each in its own transaction
+pub fn migrate(conn: &mut Connection, migrations: &[&str], name: &str) -> Result<(), Box<dyn Error>> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > migrations.len() {
+        return Err(format!("{} is at schema v{}, this build knows v{}", name, version, migrations.len()).into());
+    }
+    for (i, migration) in migrations.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+        println!("🗄️ {} migrated to v{}", name, i + 1);
+    }
+    Ok(())
+}
+
+fn parse_time(text: &str) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    Ok(DateTime::parse_from_rfc3339(text)?.with_timezone(&Utc))
+}
+
+impl Store for SqliteStore {
+    fn record_alert(&mut self, alert: &AlertRecord) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(
+            "INSERT INTO alerts (sent_at, kind, target, roi_percent, net_profit, payload) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![alert.sent_at.to_rfc3339(), alert.kind, alert.target, alert.roi_percent, alert.net_profit, alert.payload],
+        )?;
+        Ok(())
+    }
+
+    fn save_lifecycle(&mut self, records: &[&TrackedOpportunity]) -> Result<(), Box<dyn Error>> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut upsert = tx.prepare_cached(
+                "INSERT INTO lifecycle (id, first_seen, last_seen, peak_roi, current_roi, alerted_roi, closed_at, opportunity)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT (id, first_seen) DO UPDATE SET
+                    last_seen = excluded.last_seen, peak_roi = excluded.peak_roi, current_roi = excluded.current_roi,
+                    alerted_roi = excluded.alerted_roi, closed_at = excluded.closed_at, opportunity = excluded.opportunity",
+            )?;
+            for record in records {
+                upsert.execute(params![
+                    record.opportunity.id,
+                    record.first_seen.to_rfc3339(),
+                    record.last_seen.to_rfc3339(),
+                    record.peak_roi,
+                    record.current_roi,
+                    record.alerted_roi,
+                    record.closed_at.map(|t| t.to_rfc3339()),
+                    serde_json::to_string(&record.opportunity)?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_lifecycle(&self) -> Result<Vec<TrackedOpportunity>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT first_seen, last_seen, peak_roi, current_roi, alerted_roi, opportunity FROM lifecycle WHERE closed_at IS NULL",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, f64>(2)?,
+                row.get::<_, f64>(3)?,
+                row.get::<_, Option<f64>>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            let (first_seen, last_seen, peak_roi, current_roi, alerted_roi, opportunity) = row?;
+            records.push(TrackedOpportunity::restored(
+                serde_json::from_str(&opportunity)?,
+                parse_time(&first_seen)?,
+                parse_time(&last_seen)?,
+                peak_roi,
+                current_roi,
+                alerted_roi,
+            ));
+        }
+        Ok(records)
+    }
+
+    fn save_signals(&mut self, ids: &HashSet<String>) -> Result<(), Box<dyn Error>> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM signals", [])?;
+        {
+            let mut insert = tx.prepare_cached("INSERT INTO signals (id) VALUES (?1)")?;
+            for id in ids {
+                insert.execute([id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_signals(&self) -> Result<HashSet<String>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare("SELECT id FROM signals")?;
+        let ids = stmt.query_map([], |row| row.get(0))?.collect::<Result<HashSet<String>, _>>()?;
+        Ok(ids)
+    }
+
+    fn set_match_verdict(&mut self, key_a: &str, key_b: &str, confirmed: bool) -> Result<(), Box<dyn Error>> {
+        let (a, b) = MatchVerdicts::pair(key_a, key_b);
+        self.conn.execute(
+            "INSERT INTO match_verdicts (market_a, market_b, confirmed, decided_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (market_a, market_b) DO UPDATE SET confirmed = excluded.confirmed, decided_at = excluded.decided_at",
+            params![a, b, confirmed, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn match_verdicts(&self) -> Result<MatchVerdicts, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare("SELECT market_a, market_b, confirmed FROM match_verdicts")?;
+        let verdicts = stmt
+            .query_map([], |row| Ok(((row.get(0)?, row.get(1)?), row.get(2)?)))?
+            .collect::<Result<HashMap<(String, String), bool>, _>>()?;
+        Ok(MatchVerdicts { verdicts })
+    }
+
+    fn add_mute(&mut self, mute: &Mute) -> Result<(), Box<dyn Error>> {
+        self.conn.execute(
+            "INSERT INTO mutes (target, until, created_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT (target) DO UPDATE SET until = excluded.until, created_at = excluded.created_at",
+            params![mute.target, mute.until.map(|t| t.to_rfc3339()), Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn remove_mute(&mut self, target: &str) -> Result<bool, Box<dyn Error>> {
+        Ok(self.conn.execute("DELETE FROM mutes WHERE target = ?1", [target])? > 0)
+    }
+
+    fn active_mutes(&self, now: DateTime<Utc>) -> Result<Vec<Mute>, Box<dyn Error>> {
+        let mut stmt = self.conn.prepare("SELECT target, until FROM mutes WHERE until IS NULL OR until > ?1")?;
+        let rows = stmt.query_map([now.to_rfc3339()], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)))?;
+
+        let mut mutes = Vec::new();
+        for row in rows {
+            let (target, until) = row?;
+            mutes.push(Mute { target, until: until.as_deref().map(parse_time).transpose()? });
+        }
+        Ok(mutes)
+    }
+
+    fn prune(&mut self, retention: &StorageConfig, now: DateTime<Utc>) -> Result<usize, Box<dyn Error>> {
+        let alert_cutoff = (now - Duration::days(retention.alert_retention_days as i64)).to_rfc3339();
+        let lifecycle_cutoff = (now - Duration::days(retention.lifecycle_retention_days as i64)).to_rfc3339();
+
+        let tx = self.conn.transaction()?;
+        let mut deleted = tx.execute("DELETE FROM alerts WHERE sent_at < ?1", [alert_cutoff])?;
+        deleted += tx.execute("DELETE FROM lifecycle WHERE closed_at IS NOT NULL AND closed_at < ?1", [lifecycle_cutoff])?;
+        deleted += tx.execute("DELETE FROM mutes WHERE until IS NOT NULL AND until <= ?1", [now.to_rfc3339()])?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+}
+
+/// Stand-in when the database can't be opened: the engine runs, nothing persists
+pub struct NullStore;
+
+impl Store for NullStore {
+    fn record_alert(&mut self, _: &AlertRecord) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn save_lifecycle(&mut self, _: &[&TrackedOpportunity]) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn load_lifecycle(&self) -> Result<Vec<TrackedOpportunity>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+
+    fn save_signals(&mut self, _: &HashSet<String>) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn load_signals(&self) -> Result<HashSet<String>, Box<dyn Error>> {
+        Ok(HashSet::new())
+    }
+
+    fn set_match_verdict(&mut self, _: &str, _: &str, _: bool) -> Result<(), Box<dyn Error>> {
+        Err("no state store".into())
+    }
+
+    fn match_verdicts(&self) -> Result<MatchVerdicts, Box<dyn Error>> {
+        Ok(MatchVerdicts::default())
+    }
+
+    fn add_mute(&mut self, _: &Mute) -> Result<(), Box<dyn Error>> {
+        Err("no state store".into())
+    }
+
+    fn remove_mute(&mut self, _: &str) -> Result<bool, Box<dyn Error>> {
+        Err("no state store".into())
+    }
+
+    fn active_mutes(&self, _: DateTime<Utc>) -> Result<Vec<Mute>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+
+    fn prune(&mut self, _: &StorageConfig, _: DateTime<Utc>) -> Result<usize, Box<dyn Error>> {
+        Ok(0)
+    }
+}
+
+/// Open the configured store, falling back to no persistence
+pub fn open(config: &StorageConfig) -> Box<dyn Store> {
+    match SqliteStore::open(&config.path) {
+        Ok(store) => Box::new(store),
+        Err(e) => {
+            eprintln!("⚠️ State store {} unavailable ({}), running without persistence", config.path, e);
+            Box::new(NullStore)
+        }
+    }
+}
+
+/// Whether an alert touching these ids/keys is muted
+pub fn is_muted<'a>(mutes: &[Mute], mut targets: impl Iterator<Item = &'a str>) -> bool {
+    !mutes.is_empty() && targets.any(|t| mutes.iter().any(|m| m.target == t))
+}
+
+/// `mute <target> [hours]`, `unmute <target>`, `confirm-match <key> <key>`, `reject-match <key> <key>`
+pub fn run_command(command: &str, args: &[String], config: &StorageConfig) -> Result<(), Box<dyn Error>> {
+    let mut store = SqliteStore::open(&config.path)?;
+    let arg = |i: usize| args.get(i).map(String::as_str).ok_or_else(|| format!("{}: missing argument {}", command, i + 1));
+
+    match command {
+        "mute" => {
+            let hours = args.get(1).map(|h| h.parse::<f64>()).transpose()?;
+            let until = hours.map(|h| Utc::now() + Duration::seconds((h * 3600.0) as i64));
+            store.add_mute(&Mute { target: arg(0)?.to_string(), until })?;
+            match until {
+                Some(until) => println!("🔇 Muted {} until {}", arg(0)?, until.format("%Y-%m-%d %H:%M UTC")),
+                None => println!("🔇 Muted {}", arg(0)?),
+            }
+        }
+        "unmute" => {
+            if store.remove_mute(arg(0)?)? {
+                println!("🔈 Unmuted {}", arg(0)?);
+            } else {
+                println!("{} was not muted", arg(0)?);
+            }
+        }
+        "confirm-match" | "reject-match" => {
+            let confirmed = command == "confirm-match";
+            store.set_match_verdict(arg(0)?, arg(1)?, confirmed)?;
+            println!("{} {} ↔ {}", if confirmed { "✅ Confirmed" } else { "🚫 Rejected" }, arg(0)?, arg(1)?);
+        }
+        other => return Err(format!("unknown store command {}", other).into()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Opportunity;
+    use chrono::TimeZone;
+
+    fn at(days: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000, 0).unwrap() + Duration::days(days)
+    }
+
+    fn alert(sent_at: DateTime<Utc>, target: &str) -> AlertRecord {
+        AlertRecord {
+            sent_at,
+            kind: "opened".to_string(),
+            target: target.to_string(),
+            roi_percent: Some(5.0),
+            net_profit: Some(0.05),
+            payload: "{}".to_string(),
+        }
+    }
+
+    fn count(store: &SqliteStore, table: &str) -> usize {
+        store.conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn migrations_run_once_and_reject_newer_schemas() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let version = |conn: &Connection| conn.query_row("PRAGMA user_version", [], |row| row.get::<_, usize>(0)).unwrap();
+
+        migrate(&mut conn, &["CREATE TABLE a (x INTEGER)"], "Test").unwrap();
+        assert_eq!(version(&conn), 1);
+        // Already applied migrations are skipped, new ones run
+        migrate(&mut conn, &["CREATE TABLE a (x INTEGER)", "ALTER TABLE a ADD y INTEGER"], "Test").unwrap();
+        assert_eq!(version(&conn), 2);
+        conn.execute("INSERT INTO a (x, y) VALUES (1, 2)", []).unwrap();
+
+        let err = migrate(&mut conn, &["CREATE TABLE a (x INTEGER)"], "Test").unwrap_err();
+        assert_eq!(err.to_string(), "Test is at schema v2, this build knows v1");
+
+        // A failed migration leaves the version where it was
+        assert!(migrate(&mut conn, &["", "", "CREATE TABLE a (x INTEGER)"], "Test").is_err());
+        assert_eq!(version(&conn), 2);
+    }
+
+    #[test]
+    fn prune_drops_old_alerts_closed_lifecycle_and_expired_mutes() {
+        let mut store = SqliteStore::open(":memory:").unwrap();
+        let now = at(100);
+        let retention = StorageConfig { alert_retention_days: 30, lifecycle_retention_days: 10, ..StorageConfig::default() };
+
+        store.record_alert(&alert(at(60), "old")).unwrap();
+        store.record_alert(&alert(at(80), "recent")).unwrap();
+
+        let mut tracker = crate::lifecycle::OpportunityTracker::new(&Default::default());
+        tracker.observe(&[Opportunity::test("closed_long_ago", 5.0), Opportunity::test("still_open", 5.0)], at(50));
+        tracker.mark_alerted("closed_long_ago", 5.0);
+        tracker.mark_alerted("still_open", 5.0);
+        let mut records: Vec<TrackedOpportunity> = tracker.alerted().cloned().collect();
+        for record in records.iter_mut().filter(|r| r.opportunity.id == "closed_long_ago") {
+            record.closed_at = Some(at(60));
+        }
+        store.save_lifecycle(&records.iter().collect::<Vec<_>>()).unwrap();
+
+        store.add_mute(&Mute { target: "expired".to_string(), until: Some(at(99)) }).unwrap();
+        store.add_mute(&Mute { target: "later".to_string(), until: Some(at(101)) }).unwrap();
+        store.add_mute(&Mute { target: "forever".to_string(), until: None }).unwrap();
+
+        assert_eq!(store.prune(&retention, now).unwrap(), 3);
+        assert_eq!(count(&store, "alerts"), 1);
+        let open: Vec<String> = store.load_lifecycle().unwrap().into_iter().map(|r| r.opportunity.id).collect();
+        assert_eq!(open, vec!["still_open"]);
+        assert_eq!(count(&store, "lifecycle"), 1);
+        let mut muted: Vec<String> = store.active_mutes(now).unwrap().into_iter().map(|m| m.target).collect();
+        muted.sort();
+        assert_eq!(muted, vec!["forever", "later"]);
+    }
+
+    #[test]
+    fn lifecycle_signals_and_verdicts_round_trip() {
+        let mut store = SqliteStore::open(":memory:").unwrap();
+
+        let record = TrackedOpportunity::restored(Opportunity::test("a", 5.0), at(0), at(1), 6.0, 5.0, Some(5.5));
+        store.save_lifecycle(&[&record]).unwrap();
+        let loaded = store.load_lifecycle().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!((loaded[0].first_seen, loaded[0].last_seen), (at(0), at(1)));
+        assert_eq!((loaded[0].peak_roi, loaded[0].alerted_roi), (6.0, Some(5.5)));
+
+        let ids: HashSet<String> = ["x".to_string(), "y".to_string()].into();
+        store.save_signals(&ids).unwrap();
+        store.save_signals(&ids).unwrap();
+        assert_eq!(store.load_signals().unwrap(), ids);
+
+        // Verdicts are keyed on the sorted pair
+        store.set_match_verdict("Polymarket:b", "Kalshi:a", true).unwrap();
+        store.set_match_verdict("Kalshi:a", "Polymarket:b", false).unwrap();
+        assert_eq!(store.match_verdicts().unwrap().get("Polymarket:b", "Kalshi:a"), Some(false));
+    }
+}
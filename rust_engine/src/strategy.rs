@@ -0,0 +1,241 @@
+use crate::config::Config;
+use crate::cross_matcher::CrossMatch;
+use crate::engine::{ArbitrageEngine, CrossExecution, Market, MarketDependency, Opportunity, StrategyKind};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+
+// Minimum profit threshold from Roan's research ($0.05), applied to the global default.
+// A strategy section can set its own min_profit_threshold below it.
+const MIN_PROFIT_THRESHOLD: f64 = 0.05;
+
+/// Everything a strategy sees for one scan
+pub struct ScanContext<'a> {
+    pub markets: &'a [Market],
+    /// Logical dependencies (empty unless some enabled strategy uses them)
+    pub dependencies: &'a [MarketDependency],
+    /// CrossMatcher matches across every venue pair, after `Strategy::prepare`
+    pub cross_matches: &'a [CrossMatch],
+}
+
+/// Thresholds for one strategy, resolved from its config section and the global defaults
+#[derive(Debug, Clone)]
+pub struct StrategySettings {
+    pub enabled: bool,
+    /// Fraction, e.g. 0.01 for 1%
+    pub min_roi: f64,
+    pub min_profit: f64,
+    /// Annualized net return floor (fraction); undated opportunities are annualized
+    /// over `UNDATED_HORIZON_DAYS` and filtered the same way
+    pub min_annualized_roi: f64,
+    params: HashMap<String, f64>,
+}
+
+impl StrategySettings {
+    pub fn from_config(config: &Config, kind: StrategyKind) -> Self {
+        let section = config.strategies.get(kind.config_key()).cloned().unwrap_or_default();
+
+        Self {
+            enabled: section.enabled,
+            min_roi: section.min_roi_percent.unwrap_or(config.min_roi_percent) / 100.0,
+            min_profit: section.min_profit_threshold
+                .unwrap_or(config.min_profit_threshold.max(MIN_PROFIT_THRESHOLD)),
+            min_annualized_roi: section.min_annualized_roi_percent
+                .unwrap_or(config.min_annualized_roi_percent) / 100.0,
+            params: section.params,
+        }
+    }
+
+    /// Strategy-specific tuning value, e.g. `min_similarity` for cross-platform
+    pub fn param(&self, name: &str, default: f64) -> f64 {
+        self.params.get(name).copied().unwrap_or(default)
+    }
+}
+
+pub trait Strategy: Send + Sync {
+    fn kind(&self) -> StrategyKind;
+
+    /// Whether the strategy reads `ScanContext::dependencies`
+    fn uses_dependencies(&self) -> bool {
+        false
+    }
+
+    /// Annotate the scan's cross matches before any strategy runs (all markets, play money included)
+    fn prepare(&self, _engine: &ArbitrageEngine, _markets: &[Market], _matches: &mut [CrossMatch], _settings: &StrategySettings) {}
+
+    fn run(&self, engine: &ArbitrageEngine, ctx: &ScanContext, settings: &StrategySettings) -> Vec<Opportunity>;
+}
+
+/// Per-strategy timing and hit count for the scan summary
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyStats {
+    pub kind: StrategyKind,
+    pub elapsed_ms: u128,
+    pub opportunities: usize,
+}
+
+/// All known strategies, in run order
+pub fn registry() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(SinglePlatform),
+        Box::new(CrossPlatform),
+        Box::new(IntraVenue),
+        Box::new(Combinatorial),
+        Box::new(Exclusion),
+        Box::new(MultiCondition),
+        Box::new(LpBasket),
+        Box::new(Ladder),
+        Box::new(Calendar),
+    ]
+}
+
+/// YES + NO < 1.0 on one market
+pub struct SinglePlatform;
+
+impl Strategy for SinglePlatform {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::SinglePlatform
+    }
+
+    fn run(&self, engine: &ArbitrageEngine, ctx: &ScanContext, settings: &StrategySettings) -> Vec<Opportunity> {
+        ctx.markets.par_iter()
+            .filter_map(|m| engine.check_single_platform(m, settings))
+            .collect()
+    }
+}
+
+/// YES on one venue + NO on another for each CrossMatcher match, any venue pair
+/// (params: `min_confidence`, `max_resolution_risk`)
+pub struct CrossPlatform;
+
+impl Strategy for CrossPlatform {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::CrossPlatform
+    }
+
+    fn prepare(&self, engine: &ArbitrageEngine, markets: &[Market], matches: &mut [CrossMatch], settings: &StrategySettings) {
+        engine.price_cross_matches(markets, matches, settings, self.kind());
+    }
+
+    fn run(&self, _engine: &ArbitrageEngine, ctx: &ScanContext, _settings: &StrategySettings) -> Vec<Opportunity> {
+        priced_arbs(ctx, self.kind())
+    }
+}
+
+/// Same question listed twice on one venue; only needs that venue's balance
+/// (params: `min_confidence`, `max_resolution_risk`)
+pub struct IntraVenue;
+
+impl Strategy for IntraVenue {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::IntraVenue
+    }
+
+    fn prepare(&self, engine: &ArbitrageEngine, markets: &[Market], matches: &mut [CrossMatch], settings: &StrategySettings) {
+        engine.price_cross_matches(markets, matches, settings, self.kind());
+    }
+
+    fn run(&self, _engine: &ArbitrageEngine, ctx: &ScanContext, _settings: &StrategySettings) -> Vec<Opportunity> {
+        priced_arbs(ctx, self.kind())
+    }
+}
+
+/// Arbs that `prepare` attached to the cross matches
+fn priced_arbs(ctx: &ScanContext, kind: StrategyKind) -> Vec<Opportunity> {
+    ctx.cross_matches.iter()
+        .filter_map(|cm| match &cm.execution {
+            Some(CrossExecution::Arb(opp)) if opp.kind == kind => Some((**opp).clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A implies B but P(A) > P(B) (params: `min_gap`)
+pub struct Combinatorial;
+
+impl Strategy for Combinatorial {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::Combinatorial
+    }
+
+    fn uses_dependencies(&self) -> bool {
+        true
+    }
+
+    fn run(&self, engine: &ArbitrageEngine, ctx: &ScanContext, settings: &StrategySettings) -> Vec<Opportunity> {
+        engine.check_combinatorial_arbitrage(ctx.markets, ctx.dependencies, settings)
+    }
+}
+
+/// Mutually exclusive pair priced above $1 combined
+pub struct Exclusion;
+
+impl Strategy for Exclusion {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::Exclusion
+    }
+
+    fn uses_dependencies(&self) -> bool {
+        true
+    }
+
+    fn run(&self, engine: &ArbitrageEngine, ctx: &ScanContext, settings: &StrategySettings) -> Vec<Opportunity> {
+        engine.check_exclusion_arbitrage(ctx.markets, ctx.dependencies, settings)
+    }
+}
+
+/// Multi-outcome market whose prices don't sum to $1
+pub struct MultiCondition;
+
+impl Strategy for MultiCondition {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::MultiCondition
+    }
+
+    fn run(&self, engine: &ArbitrageEngine, ctx: &ScanContext, settings: &StrategySettings) -> Vec<Opportunity> {
+        engine.check_multi_condition_rebalancing(ctx.markets, settings)
+    }
+}
+
+/// LP over 3+ logically linked markets
+pub struct LpBasket;
+
+impl Strategy for LpBasket {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::LpBasket
+    }
+
+    fn uses_dependencies(&self) -> bool {
+        true
+    }
+
+    fn run(&self, engine: &ArbitrageEngine, ctx: &ScanContext, settings: &StrategySettings) -> Vec<Opportunity> {
+        engine.check_lp_arbitrage(ctx.markets, ctx.dependencies, settings)
+    }
+}
+
+/// Threshold/bracket ladders on one underlying, within and across venues
+pub struct Ladder;
+
+impl Strategy for Ladder {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::Ladder
+    }
+
+    fn run(&self, engine: &ArbitrageEngine, ctx: &ScanContext, settings: &StrategySettings) -> Vec<Opportunity> {
+        engine.check_ladder_arbitrage(ctx.markets, settings)
+    }
+}
+
+/// Same proposition at different deadlines, earlier priced above later
+pub struct Calendar;
+
+impl Strategy for Calendar {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::Calendar
+    }
+
+    fn run(&self, engine: &ArbitrageEngine, ctx: &ScanContext, settings: &StrategySettings) -> Vec<Opportunity> {
+        engine.check_calendar_arbitrage(ctx.markets, settings)
+    }
+}
@@ -0,0 +1,175 @@
+use crate::backtest::{self, BacktestReport};
+use crate::config::Config;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+
+/// Values to try for each threshold; the sweep runs their cartesian product.
+/// An empty list keeps the value from config.json.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SweepGrid {
+    #[serde(default = "default_min_roi")]
+    pub min_roi_percent: Vec<f64>,
+    #[serde(default = "default_min_profit")]
+    pub min_profit_threshold: Vec<f64>,
+    /// `min_confidence` of the cross-platform strategy, which replaced the old
+    /// 0.4 Jaccard cutoff in `check_cross_platform`
+    #[serde(default = "default_confidence")]
+    pub cross_platform_min_confidence: Vec<f64>,
+    /// CrossMatcher's pair cutoff (`matching.min_confidence`)
+    #[serde(default = "default_confidence")]
+    pub matcher_min_confidence: Vec<f64>,
+    /// `matching.max_close_gap_days`
+    #[serde(default = "default_close_gap_days")]
+    pub max_close_gap_days: Vec<u64>,
+}
+
+impl Default for SweepGrid {
+    fn default() -> Self {
+        Self {
+            min_roi_percent: default_min_roi(),
+            min_profit_threshold: default_min_profit(),
+            cross_platform_min_confidence: default_confidence(),
+            matcher_min_confidence: default_confidence(),
+            max_close_gap_days: default_close_gap_days(),
+        }
+    }
+}
+
+fn default_min_roi() -> Vec<f64> { vec![0.5, 1.0, 2.0] }
+fn default_min_profit() -> Vec<f64> { vec![0.01, 0.05, 0.10] }
+fn default_confidence() -> Vec<f64> { vec![0.4, 0.5, 0.6] }
+fn default_close_gap_days() -> Vec<u64> { vec![30, 90, 180] }
+
+/// One combination from the grid
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SweepPoint {
+    pub min_roi_percent: f64,
+    pub min_profit_threshold: f64,
+    pub cross_platform_min_confidence: f64,
+    pub matcher_min_confidence: f64,
+    pub max_close_gap_days: u64,
+}
+
+impl SweepPoint {
+    /// The base config with this point's thresholds. Per-strategy
+    /// `min_roi_percent`/`min_profit_threshold` overrides still take precedence.
+    pub fn apply(&self, base: &Config) -> Config {
+        let mut config = base.clone();
+        config.min_roi_percent = self.min_roi_percent;
+        config.min_profit_threshold = self.min_profit_threshold;
+        config.strategies.entry("cross_platform".to_string()).or_default()
+            .params.insert("min_confidence".to_string(), self.cross_platform_min_confidence);
+        config.matching.min_confidence = self.matcher_min_confidence;
+        config.matching.max_close_gap_days = self.max_close_gap_days;
+        config
+    }
+}
+
+impl SweepGrid {
+    pub fn points(&self, base: &Config) -> Vec<SweepPoint> {
+        let or_base = |values: &[f64], current: f64| if values.is_empty() { vec![current] } else { values.to_vec() };
+        let current_cross = base.strategies.get("cross_platform")
+            .and_then(|s| s.params.get("min_confidence").copied())
+            .unwrap_or(0.5);
+        let gap_days = if self.max_close_gap_days.is_empty() {
+            vec![base.matching.max_close_gap_days]
+        } else {
+            self.max_close_gap_days.clone()
+        };
+
+        let mut points = Vec::new();
+        for &min_roi_percent in &or_base(&self.min_roi_percent, base.min_roi_percent) {
+            for &min_profit_threshold in &or_base(&self.min_profit_threshold, base.min_profit_threshold) {
+                for &cross_platform_min_confidence in &or_base(&self.cross_platform_min_confidence, current_cross) {
+                    for &matcher_min_confidence in &or_base(&self.matcher_min_confidence, base.matching.min_confidence) {
+                        for &max_close_gap_days in &gap_days {
+                            points.push(SweepPoint {
+                                min_roi_percent,
+                                min_profit_threshold,
+                                cross_platform_min_confidence,
+                                matcher_min_confidence,
+                                max_close_gap_days,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        points
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepResult {
+    pub params: SweepPoint,
+    pub opportunities: usize,
+    pub cross_matches: usize,
+    /// Share of alerts still valid when repriced at the next scan
+    pub precision: Option<f64>,
+    pub theoretical_pnl: f64,
+    pub realized_pnl: Option<f64>,
+}
+
+impl SweepResult {
+    fn new(params: SweepPoint, report: &BacktestReport) -> Self {
+        Self {
+            params,
+            opportunities: report.opportunities.len(),
+            cross_matches: report.cross_matches,
+            precision: report.precision,
+            theoretical_pnl: report.theoretical_pnl,
+            realized_pnl: report.realized_pnl,
+        }
+    }
+}
+
+/// Best precision first (combinations with no alerts last), then total theoretical profit
+fn rank(results: &mut [SweepResult]) {
+    results.sort_by(|a, b| {
+        b.precision.unwrap_or(-1.0).total_cmp(&a.precision.unwrap_or(-1.0))
+            .then(b.theoretical_pnl.total_cmp(&a.theoretical_pnl))
+    });
+}
+
+/// `sweep [--grid FILE] [--hours N] [--every N] [--resolutions FILE] [--out FILE] [--top N]`
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let flags = backtest::parse_flags(args, &["grid", "hours", "every", "resolutions", "out", "top"])?;
+    let out = flags.get("out").map(String::as_str).unwrap_or("sweep_report.json");
+    let top: usize = flags.get("top").map(|t| t.parse()).transpose()?.unwrap_or(10);
+    let grid: SweepGrid = match flags.get("grid") {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+        None => SweepGrid::default(),
+    };
+
+    let base = Config::load();
+    let (total_capital, env_min_liquidity) = backtest::env_capital();
+    let (snapshots, resolutions) = backtest::load_inputs(&base, &flags)?;
+    let points = grid.points(&base);
+    println!("🧪 Sweeping {} combinations over {} scans...", points.len(), snapshots.len());
+
+    let mut results: Vec<SweepResult> = points.par_iter()
+        .map(|point| {
+            let config = point.apply(&base);
+            let report = backtest::replay(&snapshots, &config, total_capital, env_min_liquidity, &resolutions);
+            SweepResult::new(*point, &report)
+        })
+        .collect();
+    rank(&mut results);
+    fs::write(out, serde_json::to_string_pretty(&results)?)?;
+
+    let money = |v: Option<f64>| v.map(|v| format!("${:.2}", v)).unwrap_or_else(|| "-".to_string());
+    println!("{:>7} {:>7} {:>6} {:>7} {:>5} {:>6} {:>9} {:>12} {:>12}",
+        "min_roi", "profit", "cross", "matcher", "gap", "opps", "precision", "theoretical", "realized");
+    for r in results.iter().take(top) {
+        let p = &r.params;
+        println!("{:>6.2}% {:>7.2} {:>6.2} {:>7.2} {:>4}d {:>6} {:>9} {:>12} {:>12}",
+            p.min_roi_percent, p.min_profit_threshold, p.cross_platform_min_confidence, p.matcher_min_confidence,
+            p.max_close_gap_days, r.opportunities,
+            r.precision.map(|v| format!("{:.0}%", v * 100.0)).unwrap_or_else(|| "-".to_string()),
+            money(Some(r.theoretical_pnl)), money(r.realized_pnl));
+    }
+    println!("📝 All {} combinations written to {}", results.len(), out);
+    Ok(())
+}
@@ -1,7 +1,14 @@
 use reqwest::Client;
 use serde_json::json;
 use std::error::Error;
+use crate::consensus::Divergence;
 use crate::cross_matcher::CrossMatch;
+use crate::engine::CrossExecution;
+use crate::latency::LatencyStats;
+use crate::lifecycle::{EventKind, LifecycleEvent};
+use crate::market_filter::FilterStats;
+use crate::resolution::ResolutionDiff;
+use crate::strategy::StrategyStats;
 
 pub struct TelegramNotifier {
     client: Client,
@@ -19,47 +26,82 @@ impl TelegramNotifier {
     }
 
     pub async fn send_opportunity(&self, opp: &crate::engine::Opportunity) -> Result<(), Box<dyn Error>> {
-        let url_section = if !opp.url_a.is_empty() && opp.url_a == opp.url_b {
-            format!("\n🔗 [View Market]({})", opp.url_a)
-        } else if !opp.url_a.is_empty() && !opp.url_b.is_empty() {
-            format!("\n🔗 [Market A]({})|[Market B]({})", opp.url_a, opp.url_b)
-        } else if !opp.url_a.is_empty() {
-            format!("\n🔗 [View Market]({})", opp.url_a)
-        } else {
-            String::new()
+        // One link per distinct market, in leg order
+        let mut links: Vec<&str> = Vec::new();
+        for leg in &opp.legs {
+            if !leg.url.is_empty() && !links.contains(&leg.url.as_str()) {
+                links.push(&leg.url);
+            }
+        }
+        let url_section = match links.len() {
+            0 => String::new(),
+            1 => format!("\n🔗 [View Market]({})", links[0]),
+            _ => format!(
+                "\n🔗 {}",
+                links.iter().enumerate()
+                    .map(|(i, url)| format!("[Market {}]({})", i + 1, url))
+                    .collect::<Vec<_>>()
+                    .join("|")
+            ),
         };
 
+        let legs_section = opp.legs.iter()
+            .map(|leg| format!("├ {}: ${:.4}", leg.describe(), leg.cost()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let confirmed = opp.confirmation.as_ref()
+            .map(|c| format!(
+                "\n✅ *Confirmed* {}ms after snapshot (refetch {}ms, snapshot net ${:.4})",
+                c.snapshot_to_confirm_ms, c.refetch_ms, c.snapshot_net_profit
+            ))
+            .unwrap_or_default();
+
+        let annualized = opp.annualized_roi_percent
+            .map(|a| format!("{:.1}%", a))
+            .unwrap_or_else(|| "n/a".to_string());
+        let resolves = opp.days_to_resolution
+            .map(|d| format!("in {:.0} days", d))
+            .unwrap_or_else(|| format!("unknown (annualized over {:.0} days)", crate::engine::UNDATED_HORIZON_DAYS));
+
         let message = format!(
             "🎯 *{} ARBITRAGE ALERT*\n\n\
             ━━━━━━━━━━━━━━━━━━━━\n\
-            📈 *ACTION*:\n{}\n\n\
+            📈 *ACTION* ({} legs):\n{}\n\n\
             💰 *FINANCIALS*:\n\
-            ├ Buy YES: ${:.4}\n\
-            ├ Buy NO: ${:.4}\n\
             ├ Total Cost: ${:.4}\n\
+            ├ Fees: ${:.4}\n\
             ├ Gross Profit: ${:.4}\n\
             ├ Net After Fees: ${:.4}\n\
-            └ *ROI: {:.2}%*\n\n\
+            ├ *ROI: {:.2}%*\n\
+            └ *Annualized: {}*\n\n\
+            ⏳ *Resolves*: {}\n\n\
             💵 *Position Size*: ${:.2}\n\
-            (25% Kelly Criterion)\n\n\
-            📝 *Market*:\n{}\n\n\
-            🏦 *Platforms*: {} ↔ {}{}\n\
+            ({})\n\n\
+            📝 *Market*:\n{}{}\n\n\
+            🏦 *Platforms*: {}{}{}\n\
             ━━━━━━━━━━━━━━━━━━━━\n\
-            ⚡ _Rust HFT Engine | {}ms latency_",
-            opp.opp_type.to_uppercase(),
-            opp.action,
-            opp.buy_yes_price,
-            opp.buy_no_price,
+            ⚡ _{}_",
+            opp.kind.label().to_uppercase(),
+            opp.legs.len(),
+            legs_section,
             opp.total_cost,
+            opp.total_fees(),
             opp.gross_profit,
             opp.net_profit_after_fees,
             opp.roi_percent,
+            annualized,
+            resolves,
             opp.suggested_position,
+            opp.sizing_limit.as_ref()
+                .map(|limit| format!("capped by {}", limit))
+                .unwrap_or_else(|| "25% Kelly Criterion".to_string()),
             opp.description,
-            opp.platform_a,
-            opp.platform_b,
+            opp.resolution.as_ref().map(resolution_section).unwrap_or_default(),
+            opp.platforms().join(" ↔ "),
             url_section,
-            chrono::Utc::now().timestamp_millis() % 1000
+            confirmed,
+            latency_line(opp)
         );
 
         let url = format!(
@@ -82,6 +124,70 @@ impl TelegramNotifier {
         Ok(())
     }
 
+    /// Alert for a lifecycle event: the full opportunity when it opens, a short
+    /// update when its ROI moves or it closes
+    pub async fn send_event(&self, event: &LifecycleEvent) -> Result<(), Box<dyn Error>> {
+        let record = &event.record;
+        let opp = &record.opportunity;
+        let open_minutes = (record.closed_at.unwrap_or_else(chrono::Utc::now) - record.first_seen).num_minutes();
+
+        let message = match event.kind {
+            EventKind::Opened => return self.send_opportunity(opp).await,
+            EventKind::Changed => format!(
+                "🔄 *{} CHANGED*\n\n\
+                ━━━━━━━━━━━━━━━━━━━━\n\
+                📈 *ROI*: {:.2}% → {:.2}%\n\
+                🏔️ *Peak*: {:.2}%\n\
+                💵 *Net Profit*: ${:.4}\n\
+                ⏱️ *Open*: {}m\n\
+                📝 {}\n\
+                ━━━━━━━━━━━━━━━━━━━━\n\
+                _{}_",
+                opp.kind.label().to_uppercase(),
+                record.alerted_roi.unwrap_or(record.current_roi),
+                record.current_roi,
+                record.peak_roi,
+                opp.net_profit_after_fees,
+                open_minutes,
+                opp.description,
+                latency_line(opp)
+            ),
+            EventKind::Closed => format!(
+                "🔚 *{} CLOSED*\n\n\
+                ━━━━━━━━━━━━━━━━━━━━\n\
+                📈 *Last ROI*: {:.2}%\n\
+                🏔️ *Peak*: {:.2}%\n\
+                ⏱️ *Open*: {}m\n\
+                📝 {}\n\
+                ━━━━━━━━━━━━━━━━━━━━",
+                opp.kind.label().to_uppercase(),
+                record.current_roi,
+                record.peak_roi,
+                open_minutes,
+                opp.description
+            ),
+        };
+
+        let url = format!(
+            "https://api.telegram.org/bot{}/sendMessage",
+            self.bot_token
+        );
+
+        let payload = json!({
+            "chat_id": self.chat_id,
+            "text": message,
+            "parse_mode": "Markdown"
+        });
+
+        self.client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn send_startup_message(&self) -> Result<(), Box<dyn Error>> {
         let message = "🚀 *RUST HFT ARBITRAGE BOT ONLINE*\n\n\
             ━━━━━━━━━━━━━━━━━━━━\n\
@@ -90,7 +196,10 @@ impl TelegramNotifier {
             🔍 *Strategies*:\n\
             ├ Single-Platform (YES+NO<1)\n\
             ├ Cross-Platform (Roan's Method)\n\
-            └ Heuristic Matching (Entity+Category+Team+Year)\n\n\
+            ├ Exclusion (NO on Exclusive Pairs)\n\
+            ├ LP Basket (Logical Constraints)\n\
+            ├ Heuristic Matching (Entity+Category+Team+Year)\n\
+            └ Divergence (Liquidity-Weighted Consensus)\n\n\
             💰 *Fee Calculation*: Enabled\n\
             📊 *Position Sizing*: 25% Kelly\n\
             ━━━━━━━━━━━━━━━━━━━━\n\
@@ -116,16 +225,33 @@ impl TelegramNotifier {
         Ok(())
     }
 
-    pub async fn send_summary(&self, total_markets: usize, opportunities: usize, scan_time_ms: u64) -> Result<(), Box<dyn Error>> {
+    pub async fn send_summary(&self, total_markets: usize, opportunities: usize, scan_time_ms: u64, strategies: &[StrategyStats], filters: &FilterStats, latency: &LatencyStats) -> Result<(), Box<dyn Error>> {
         // Only send if opportunities found
         if opportunities == 0 {
             return Ok(());
         }
 
+        let per_strategy = strategies.iter()
+            .map(|s| format!("├ {}: {} in {}ms", s.kind.label(), s.opportunities, s.elapsed_ms))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let filtered = if filters.total_dropped() > 0 {
+            format!("\nFiltered: {} ({})", filters.total_dropped(), filters.describe())
+        } else {
+            String::new()
+        };
+
+        let latency = if latency.is_empty() {
+            String::new()
+        } else {
+            format!("\nLatency p50/p95: {}", latency.describe())
+        };
+
         let message = format!(
             "📊 *Scan Summary*\n\
-            Markets: {} | Opps: {} | Time: {}ms",
-            total_markets, opportunities, scan_time_ms
+            Markets: {} | Opps: {} | Time: {}ms{}{}\n{}",
+            total_markets, opportunities, scan_time_ms, filtered, latency, per_strategy
         );
 
         let url = format!(
@@ -148,6 +274,66 @@ impl TelegramNotifier {
         Ok(())
     }
 
+    pub async fn send_divergence(&self, d: &Divergence) -> Result<(), Box<dyn Error>> {
+        let venues = d.venues.iter()
+            .map(|v| {
+                let link = if v.url.is_empty() {
+                    v.platform.clone()
+                } else {
+                    format!("[{}]({})", v.platform, v.url)
+                };
+                format!(
+                    "{} {}: ${:.3} ({:+.1}pp, z {:+.1}) | liq {:.0}{}",
+                    if v.flagged { "⚠️" } else { "├" },
+                    link,
+                    v.price,
+                    v.gap * 100.0,
+                    v.z_score,
+                    v.liquidity,
+                    if v.tradable { "" } else { " (play money)" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let question = d.venues.first().map(|v| v.question.as_str()).unwrap_or_default();
+
+        let message = format!(
+            "📐 *DIVERGENCE ALERT*\n\n\
+            ━━━━━━━━━━━━━━━━━━━━\n\
+            📊 *Category*: {}\n\
+            📝 *Market*: {}\n\n\
+            🎯 *Consensus*: ${:.3} (σ {:.3})\n\n\
+            🏦 *Venues*:\n{}\n\
+            ━━━━━━━━━━━━━━━━━━━━",
+            d.category.to_uppercase(),
+            question,
+            d.consensus,
+            d.std_dev,
+            venues,
+        );
+
+        let url = format!(
+            "https://api.telegram.org/bot{}/sendMessage",
+            self.bot_token
+        );
+
+        let payload = json!({
+            "chat_id": self.chat_id,
+            "text": message,
+            "parse_mode": "Markdown",
+            "disable_web_page_preview": true
+        });
+
+        self.client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn send_cross_match(&self, m: &CrossMatch) -> Result<(), Box<dyn Error>> {
         let url_section = if !m.url_a.is_empty() && !m.url_b.is_empty() {
             format!("\n🔗 [Market A]({})|[Market B]({})", m.url_a, m.url_b)
@@ -155,8 +341,32 @@ impl TelegramNotifier {
             String::new()
         };
 
+        let title = if !m.tradable {
+            "CROSS-PLATFORM SIGNAL (play money, not tradable)"
+        } else if m.platform_a == m.platform_b {
+            "SAME-VENUE DUPLICATE"
+        } else {
+            "CROSS-PLATFORM MATCH"
+        };
+
+        let execution = match &m.execution {
+            Some(CrossExecution::Arb(opp)) => format!(
+                "\n✅ *Arb*: {}\n└ Cost ${:.4} | Net ${:.4} | ROI {:.2}%",
+                opp.legs.iter().map(|l| l.describe()).collect::<Vec<_>>().join(" + "),
+                opp.total_cost,
+                opp.net_profit_after_fees,
+                opp.roi_percent
+            ),
+            Some(CrossExecution::NoArbAfterFees { total_cost, fees, net_profit }) => format!(
+                "\n❌ *No arb after fees*: cost ${:.4} + fees ${:.4} → net ${:.4}",
+                total_cost, fees, net_profit
+            ),
+            Some(CrossExecution::NotExecutable { reason }) => format!("\n📡 *Not executable*: {}", reason),
+            None => String::new(),
+        };
+
         let message = format!(
-            "🔗 *CROSS-PLATFORM MATCH*\n\n\
+            "🔗 *{}*\n\n\
             ━━━━━━━━━━━━━━━━━━━━\n\
             📊 *Category*: {}\n\
             📈 *Confidence*: {:.0}%\n\n\
@@ -164,9 +374,10 @@ impl TelegramNotifier {
             {} (YES: ${:.3})\n\n\
             🅱️ *{}*:\n\
             {} (YES: ${:.3})\n\n\
-            💰 *Price Diff*: {:.1}%\n\
-            🏷️ *Entities*: {}{}\n\
+            💰 *Price Diff*: {:.1}%{}\n\
+            🏷️ *Entities*: {}{}{}\n\
             ━━━━━━━━━━━━━━━━━━━━",
+            title,
             m.category.to_uppercase(),
             m.confidence * 100.0,
             m.platform_a,
@@ -176,7 +387,9 @@ impl TelegramNotifier {
             m.question_b,
             m.yes_price_b,
             m.price_diff * 100.0,
+            execution,
             m.shared_entities.join(", "),
+            resolution_section(&m.resolution),
             url_section,
         );
 
@@ -200,3 +413,30 @@ impl TelegramNotifier {
         Ok(())
     }
 }
+
+/// Resolution-risk line for cross-venue alerts
+fn resolution_section(diff: &ResolutionDiff) -> String {
+    let flags = diff.flags();
+    if flags.is_empty() {
+        format!("\n⚖️ *Resolution Risk*: {:.0}%", diff.risk * 100.0)
+    } else {
+        format!("\n⚖️ *Resolution Risk*: {:.0}% ({})", diff.risk * 100.0, flags.join("; "))
+    }
+}
+
+/// "data 1200ms old | venue 3400ms | analysis 310ms | +1800ms to alert"
+fn latency_line(opp: &crate::engine::Opportunity) -> String {
+    let t = &opp.timing;
+    let mut parts = Vec::new();
+    if let Some(age) = t.data_age_ms {
+        parts.push(format!("data {}ms old", age));
+    }
+    if let Some(age) = t.venue_age_ms {
+        parts.push(format!("venue {}ms", age));
+    }
+    parts.push(format!("analysis {}ms", t.analysis_ms));
+    if let Some(oldest) = opp.oldest_data() {
+        parts.push(format!("+{}ms to alert", (chrono::Utc::now() - oldest).num_milliseconds()));
+    }
+    format!("Rust HFT Engine | {}", parts.join(" | "))
+}